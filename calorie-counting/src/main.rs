@@ -1,100 +1,889 @@
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::error::Error;
 use std::fmt::Display;
-use std::num::ParseIntError;
+use std::num::{NonZeroUsize, ParseIntError};
 use std::str::FromStr;
 
-use input::Description;
+use input::{Answers, Description, Flag, Flags, SomeError, SplitMix64};
 
 fn main() {
-    input::with(
+    input::with_parts(
         Description {
-            name: "calorie-counting",
-            bin_name: "calorie-counting".into(),
-            description: "\
+            flags: &[
+                Flag {
+                    name: "all",
+                    value: None,
+                    help: "Also print every sum in descending order; sorts all sums",
+                },
+                Flag {
+                    name: "stats",
+                    value: None,
+                    help: "Also print the smallest, median, mean, and 90th percentile sums",
+                },
+                Flag {
+                    name: "which",
+                    value: None,
+                    help: "Also print the 1-based numbers of the elves with the largest 3 sums",
+                },
+                Flag {
+                    name: "min",
+                    value: None,
+                    help: "Also print the smallest sum",
+                },
+                Flag {
+                    name: "largest-ration",
+                    value: None,
+                    help: "Also print the largest single ration of any elf",
+                },
+                Flag {
+                    name: "kth",
+                    value: Some("K"),
+                    help: "Only print the K-th largest sum, where 1 is the largest",
+                },
+                Flag {
+                    name: "histogram",
+                    value: None,
+                    help: "Also print a histogram of the sums; see '--buckets'",
+                },
+                // a count after '--histogram' couldn't be told apart from FILE,
+                // so it takes its own flag
+                Flag {
+                    name: "buckets",
+                    value: Some("N"),
+                    help: "Use N buckets for '--histogram', 10 by default",
+                },
+                Flag {
+                    name: "sample",
+                    value: Some("K"),
+                    help: "Also print the sums of K elves chosen at random; see '--seed'",
+                },
+                Flag {
+                    name: "seed",
+                    value: Some("SEED"),
+                    help: "Choose elves for '--sample' reproducibly from the integer SEED, 0 by default",
+                },
+                Flag {
+                    name: "blanks",
+                    value: Some("N"),
+                    help: "Separate elves by N consecutive empty lines, ignoring fewer",
+                },
+                Flag {
+                    name: "ration-sep",
+                    value: Some("SEP"),
+                    help: "Separate rations by SEP instead of a newline; see '--group-sep'",
+                },
+                Flag {
+                    name: "group-sep",
+                    value: Some("SEP"),
+                    help: "Separate elves by SEP instead of an empty line, as in '1;2||3'",
+                },
+                Flag {
+                    name: "comma-rations",
+                    value: None,
+                    help: "Also take a line of comma separated numbers as several rations",
+                },
+                Flag {
+                    name: "on-parse-error",
+                    value: Some("POLICY"),
+                    help: "On a line that isn't a number, 'fail', 'skip' it, or count it as 'zero'",
+                },
+            ],
+            examples: &[
+                "calorie-counting input.txt",
+                "cat input.txt | calorie-counting --stdin",
+                "calorie-counting --stats --histogram input.txt",
+            ],
+            ..input::description!(
+                "\
 Takes a list of numbers, zero or one per line.
 Sums all consecutive numbers not separated by an empty line,
-then returns the largest sum and the sum of the largest 3 sums.",
-            version: (0, 1, 0),
+then returns the largest sum and the sum of the largest 3 sums."
+            )
         },
-        |input| {
-            let elves = input.parse::<Elves>()?;
+        |input, flags| {
+            Elves::parse(input, ParseOptions::from_flags(flags)?)?;
+            Ok(())
+        },
+        |input, flags| {
+            let elves = Elves::parse(input, ParseOptions::from_flags(flags)?)?;
+            if let Some(k) = flags.parse("kth")? {
+                let Some(kth) = elves.kth_largest(k) else {
+                    let count = elves.elves.len();
+                    return Err(SomeError::msg(format!(
+                        "'--kth' expects a rank from 1 to the number of elves, {count}"
+                    )));
+                };
+                return Ok(Answers::other(kth));
+            }
+
             let top = elves.sum_calories_top::<1>();
             let top_three = elves.sum_calories_top::<3>();
 
-            println!("{top}");
-            println!("{top_three}");
+            if !["all", "which", "min", "largest-ration", "stats", "histogram", "sample"]
+                .iter()
+                .any(|&flag| flags.is_set(flag))
+            {
+                return Ok(Answers::parts(top, top_three));
+            }
 
-            Ok(())
+            let mut lines = vec![top.to_string(), top_three.to_string()];
+            if flags.is_set("all") {
+                lines.extend(elves.totals_sorted().iter().map(u64::to_string));
+            }
+
+            if flags.is_set("which") {
+                let elves = elves.top_n_indices(3).into_iter();
+                let elves: Vec<_> = elves.map(|index| (index + 1).to_string()).collect();
+                lines.push(elves.join(" "));
+            }
+
+            if flags.is_set("min") {
+                lines.extend(elves.min_calories().map(|min| min.to_string()));
+            }
+
+            if flags.is_set("largest-ration") {
+                let rations = elves.groups().flatten().map(Ration::calories);
+                lines.extend(rations.max().map(|max| max.to_string()));
+            }
+
+            if flags.is_set("stats") {
+                if let (Some(min), Some(median), Some(mean), Some(p90)) = (
+                    elves.min_calories(),
+                    elves.median(),
+                    elves.mean(),
+                    elves.percentile(90.0),
+                ) {
+                    lines.push(format!("min: {min}"));
+                    lines.push(format!("median: {median}"));
+                    lines.push(format!("mean: {mean:.2}"));
+                    lines.push(format!("p90: {p90}"));
+                }
+            }
+
+            if flags.is_set("histogram") {
+                let buckets = flags.parse("buckets")?.unwrap_or(DEFAULT_BUCKETS);
+                lines.extend(histogram(&elves.histogram(buckets)));
+            }
+
+            if let Some(k) = flags.parse("sample")? {
+                let sample = elves.sample(k, flags.parse("seed")?.unwrap_or(0));
+                let sample: Vec<_> = sample.iter().map(u64::to_string).collect();
+                lines.push(sample.join(" "));
+            }
+
+            Ok(Answers::other(lines.join("\n")))
         },
     );
 }
 
+const DEFAULT_BUCKETS: NonZeroUsize = NonZeroUsize::new(10).unwrap();
+
+/// The length of the bar of the bucket with the most elves.
+const HISTOGRAM_WIDTH: usize = 50;
+
+/// Draw each bucket as a line with its range, a bar of `#` scaled to the largest count,
+/// and its count.
+fn histogram(buckets: &[Bucket]) -> Vec<String> {
+    let largest = buckets.iter().map(|bucket| bucket.elves).max().unwrap_or(0);
+    let ranges: Vec<_> = buckets
+        .iter()
+        .map(|bucket| format!("{}..={}", bucket.start, bucket.end))
+        .collect();
+    let width = ranges.iter().map(String::len).max().unwrap_or(0);
+
+    buckets
+        .iter()
+        .zip(ranges)
+        .map(|(bucket, range)| {
+            let elves = bucket.elves;
+            // any elves at all get at least one '#', so no bucket looks empty when it isn't
+            let bar = (elves * HISTOGRAM_WIDTH).div_ceil(largest.max(1));
+            format!("{range:>width$} | {} {elves}", "#".repeat(bar))
+        })
+        .collect()
+}
+
+/// How input is parsed, as configured on the command line.
+#[derive(Clone, Copy)]
+struct ParseOptions<'a> {
+    /// The number of consecutive empty lines separating elves.
+    separator_blanks: NonZeroUsize,
+    /// Separate rations and elves by these instead of by lines.
+    separators: Option<Separators<'a>>,
+    /// Split each line on commas, so an elf's rations can be on one line.
+    comma_rations: bool,
+    on_error: ErrorPolicy,
+}
+
+impl<'a> ParseOptions<'a> {
+    fn from_flags(flags: &'a Flags) -> Result<Self, SomeError> {
+        let separators = match (flags.value("ration-sep"), flags.value("group-sep")) {
+            (None, None) => None,
+            (ration, group) => Some(Separators::new(
+                ration.unwrap_or(Separators::LINES.ration),
+                group.unwrap_or(Separators::LINES.group),
+            )?),
+        };
+        if separators.is_some() && flags.is_set("blanks") {
+            return Err(SomeError::msg(
+                "'--blanks' can't be used with '--ration-sep' or '--group-sep'",
+            ));
+        }
+
+        Ok(Self {
+            separator_blanks: flags.parse("blanks")?.unwrap_or(NonZeroUsize::MIN),
+            separators,
+            comma_rations: flags.is_set("comma-rations"),
+            on_error: flags.parse("on-parse-error")?.unwrap_or_default(),
+        })
+    }
+}
+
+impl Default for ParseOptions<'_> {
+    fn default() -> Self {
+        Self {
+            separator_blanks: NonZeroUsize::MIN,
+            separators: None,
+            comma_rations: false,
+            on_error: ErrorPolicy::default(),
+        }
+    }
+}
+
+/// The separators between rations and between elves, as set with `--ration-sep` and `--group-sep`.
+#[derive(Clone, Copy)]
+struct Separators<'a> {
+    ration: &'a str,
+    group: &'a str,
+}
+
+impl<'a> Separators<'a> {
+    /// Rations on their own lines, and elves separated by an empty line.
+    const LINES: Separators<'static> = Separators {
+        ration: "\n",
+        group: "\n\n",
+    };
+
+    /// Check that the separators can be told apart; neither can be empty,
+    /// and the group separator can't be part of the ration separator, which would split it.
+    fn new(ration: &'a str, group: &'a str) -> Result<Self, SomeError> {
+        if ration.is_empty() || group.is_empty() {
+            return Err(SomeError::msg(
+                "'--ration-sep' and '--group-sep' can't be empty",
+            ));
+        }
+        if ration.contains(group) {
+            return Err(SomeError::msg(
+                "'--ration-sep' and '--group-sep' must differ, with '--group-sep' not part of '--ration-sep'",
+            ));
+        }
+
+        Ok(Self { ration, group })
+    }
+}
+
+/// What to do with a line that isn't a number.
+#[derive(Clone, Copy, Default)]
+enum ErrorPolicy {
+    /// Stop parsing with an error.
+    #[default]
+    Fail,
+    /// Leave the line out, with a note on stderr.
+    Skip,
+    /// Count the line as a ration of 0 calories.
+    Zero,
+}
+
+impl FromStr for ErrorPolicy {
+    type Err = UnknownErrorPolicy;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "fail" => Ok(Self::Fail),
+            "skip" => Ok(Self::Skip),
+            "zero" => Ok(Self::Zero),
+            _ => Err(UnknownErrorPolicy),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct UnknownErrorPolicy;
+
+impl Error for UnknownErrorPolicy {}
+
+impl Display for UnknownErrorPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected one of 'fail', 'skip', or 'zero'")
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Elves {
     elves: Vec<Elf>,
+    /// The calorie sums sorted ascending, once any statistic needs them.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    sorted: OnceCell<Vec<u64>>,
 }
 
 impl Elves {
+    /// Iterate over the calorie sum of every elf.
+    fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.elves.iter().map(Elf::total)
+    }
+
+    /// Iterate over the rations of every elf, in the order they were listed.
+    fn groups(&self) -> impl Iterator<Item = &[Ration]> + '_ {
+        self.elves.iter().map(|elf| elf.rations.as_slice())
+    }
+
+    /// Returns the sum of the `N` largest calorie sums, of the elves from [`Self::top_n_indices`].
     fn sum_calories_top<const N: usize>(&self) -> u64 {
-        self.elves
+        self.top_n_indices(N)
             .iter()
-            .map(|elf| elf.rations.iter().map(|ration| ration.calories).sum())
-            .fold([0; N], |mut tops, calories: u64| {
-                tops.sort_unstable();
+            .map(|&index| self.elves[index].total())
+            .sum()
+    }
 
-                if let Some(lowest) = tops.first_mut() {
-                    *lowest = calories.max(*lowest);
-                }
+    /// Returns the indices of the `top` elves with the largest calorie sums, largest first.
+    ///
+    /// Elves with equal sums are ordered by index, so on a tie at the boundary,
+    /// the elf with the lowest index is selected.
+    ///
+    /// Only the `top` elves seen so far are kept, in a min-heap whose top is the one
+    /// to drop next, taking `O(n log top)` time and `O(top)` space.
+    fn top_n_indices(&self, top: usize) -> Vec<usize> {
+        // the larger sum ranks higher, then the lower index
+        let mut largest = BinaryHeap::with_capacity(top + 1);
+        for (index, total) in self.iter().enumerate() {
+            largest.push(Reverse((total, Reverse(index))));
+            if largest.len() > top {
+                largest.pop();
+            }
+        }
 
-                tops
+        let largest = largest.into_sorted_vec().into_iter();
+        largest.map(|Reverse((_, Reverse(index)))| index).collect()
+    }
+
+    /// Returns the `k`-th largest calorie sum, where `k` is 1-based, so 1 is the largest,
+    /// or [`None`] if `k` is 0 or there are fewer than `k` elves.
+    ///
+    /// Only the `k` largest sums seen so far are kept, in a min-heap whose top is the `k`-th,
+    /// taking `O(n log k)` time and `O(k)` space.
+    fn kth_largest(&self, k: usize) -> Option<u64> {
+        if k == 0 || k > self.elves.len() {
+            return None;
+        }
+
+        let mut largest = BinaryHeap::with_capacity(k + 1);
+        for total in self.iter() {
+            largest.push(Reverse(total));
+            if largest.len() > k {
+                largest.pop();
+            }
+        }
+
+        largest.peek().map(|&Reverse(kth)| kth)
+    }
+
+    /// Divide the range from the smallest to the largest calorie sum into at most `buckets`
+    /// equally wide buckets, then count the elves with a sum in each one.
+    ///
+    /// All buckets are returned in order, including empty ones between others,
+    /// but there are fewer when the range is too narrow to divide,
+    /// down to a single bucket if all sums are equal; without elves there are none.
+    fn histogram(&self, buckets: NonZeroUsize) -> Vec<Bucket> {
+        let (Some(min), Some(max)) = (self.iter().min(), self.iter().max()) else {
+            return Vec::new();
+        };
+
+        // every sum in the range needs a bucket, so widths round up
+        let sums = u128::from(max - min) + 1;
+        let width = sums.div_ceil(buckets.get() as u128);
+        let count = sums.div_ceil(width) as usize;
+
+        let mut histogram: Vec<_> = (0..count)
+            .map(|index| {
+                let start = u128::from(min) + index as u128 * width;
+                let end = (start + width - 1).min(u128::from(max));
+                Bucket {
+                    start: start as u64,
+                    end: end as u64,
+                    elves: 0,
+                }
             })
+            .collect();
+        for total in self.iter() {
+            let index = u128::from(total - min) / width;
+            histogram[index as usize].elves += 1;
+        }
+
+        histogram
+    }
+
+    /// Returns the calorie sums of `k` elves chosen at random, in order of index,
+    /// or of every elf if there are no more than `k`.
+    ///
+    /// Elves are chosen by reservoir sampling in a single pass over [`Self::iter`]:
+    /// the first `k` sums fill the sample, then the sum at each later index `i`
+    /// replaces a sum of the sample chosen at random with probability `k / (i + 1)`,
+    /// so every elf is equally likely to be chosen while only `k` sums are kept.
+    /// The same seed always chooses the same elves.
+    fn sample(&self, k: usize, seed: u64) -> Vec<u64> {
+        let mut rng = SplitMix64::new(seed);
+        let mut sample: Vec<(usize, u64)> = Vec::with_capacity(k);
+
+        for (index, total) in self.iter().enumerate() {
+            if sample.len() < k {
+                sample.push((index, total));
+                continue;
+            }
+
+            let replace = (rng.next_u64() % (index as u64 + 1)) as usize;
+            if let Some(chosen) = sample.get_mut(replace) {
+                *chosen = (index, total);
+            }
+        }
+
+        sample.sort_unstable_by_key(|&(index, _)| index);
+        sample.into_iter().map(|(_, total)| total).collect()
+    }
+
+    /// Returns the smallest calorie sum, or [`None`] if there are no elves.
+    fn min_calories(&self) -> Option<u64> {
+        self.iter().min()
+    }
+
+    /// Returns the calorie sum of every elf, largest first.
+    ///
+    /// Unlike [`Self::sum_calories_top`], which only keeps the elves with the top `N` sums,
+    /// this materializes and sorts all sums, taking `O(n log n)` time and `O(n)` space.
+    fn totals_sorted(&self) -> Vec<u64> {
+        self.sorted_ascending().iter().rev().copied().collect()
+    }
+
+    /// Returns the calorie sums sorted ascending, sorting them only the first time,
+    /// so any number of statistics can share a single sort.
+    fn sorted_ascending(&self) -> &[u64] {
+        self.sorted.get_or_init(|| {
+            let mut totals: Vec<u64> = self.iter().collect();
+            totals.sort_unstable();
+            totals
+        })
+    }
+
+    /// Returns the median calorie sum, the lower of the middle two for an even count.
+    fn median(&self) -> Option<u64> {
+        self.percentile(50.0)
+    }
+
+    /// Returns the `p`th percentile calorie sum using the nearest-rank method,
+    /// or [`None`] if there are no elves.
+    ///
+    /// With the `n` sums sorted ascending, the sum at the 1-based rank `ceil(p / 100 * n)`
+    /// is returned; `p` is clamped to `0..=100`, and a rank of 0 is treated as 1.
+    fn percentile(&self, p: f64) -> Option<u64> {
+        let totals = self.sorted_ascending();
+
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * totals.len() as f64).ceil() as usize;
+        totals.get(rank.max(1) - 1).copied()
+    }
+
+    /// Returns the mean calorie sum, or [`None`] if there are no elves.
+    fn mean(&self) -> Option<f64> {
+        let (count, sum) = self
             .iter()
-            .sum()
+            .fold((0_u64, 0), |(count, sum), total| (count + 1, sum + total));
+
+        (count > 0).then(|| sum as f64 / count as f64)
     }
 }
 
+/// The elves with a calorie sum from `start` to `end`, inclusive.
+struct Bucket {
+    start: u64,
+    end: u64,
+    elves: usize,
+}
+
 impl FromStr for Elves {
     type Err = ParseError;
 
     fn from_str(calories: &str) -> Result<Self, ParseError> {
+        Self::parse(calories, ParseOptions::default())
+    }
+}
+
+impl Elves {
+    /// Parse elves separated by `separator_blanks` consecutive empty lines.
+    ///
+    /// Any shorter run of empty lines is ignored, so the rations around it belong to the same elf.
+    /// The last elf is parsed whether or not empty lines follow it.
+    /// A line that isn't a number is handled according to the policy,
+    /// and either way isn't an empty line.
+    /// As with separators, a group without any rations isn't an elf,
+    /// wherever it is, as when all of its lines are skipped
+    /// or longer runs of empty lines separate elves.
+    ///
+    /// With `comma_rations`, each line is split on commas into rations,
+    /// with whitespace around them trimmed and empty ones, as after a trailing comma, left out;
+    /// a line of a single number is still one ration, so both styles can be mixed.
+    ///
+    /// With separators, elves are parsed as with [`Self::try_from_with_seps`] instead.
+    fn parse(calories: &str, options: ParseOptions) -> Result<Self, ParseError> {
+        let ParseOptions {
+            separator_blanks,
+            separators,
+            comma_rations,
+            on_error,
+        } = options;
+        if let Some(separators) = separators {
+            return Self::try_from_with_seps(calories, separators, options);
+        }
+
         let mut elves = Vec::new();
         let mut rations = Vec::new();
+        let mut blanks = 0;
 
-        for line in calories.lines() {
+        for (index, line) in calories.lines().enumerate() {
             if line.is_empty() {
-                elves.push(Elf { rations });
-                rations = Vec::new();
+                blanks += 1;
+                if blanks == separator_blanks.get() {
+                    if !rations.is_empty() {
+                        elves.push(Elf { rations });
+                        rations = Vec::new();
+                    }
+                    blanks = 0;
+                }
+                continue;
+            }
+
+            blanks = 0;
+            let tokens: Vec<&str> = if comma_rations {
+                let tokens = line.split(',').map(str::trim);
+                tokens.filter(|token| !token.is_empty()).collect()
             } else {
-                let calories = line.parse::<u64>().map_err(ParseError)?;
-                rations.push(Ration { calories });
+                vec![line]
+            };
+            for token in tokens {
+                rations.extend(Ration::parse(token, index + 1, on_error)?);
             }
         }
+        // the last elf needn't be followed by empty lines
+        if !rations.is_empty() {
+            elves.push(Elf { rations });
+        }
 
-        Ok(Self { elves })
+        Ok(Self {
+            elves,
+            sorted: OnceCell::new(),
+        })
+    }
+
+    /// Parse elves separated by `separators.group`, each with rations separated by
+    /// `separators.ration`, so all of them can be on one line, as in `1;2||3`.
+    ///
+    /// Whitespace around each ration is trimmed, and empty rations are left out,
+    /// so a trailing newline or separator doesn't add a ration;
+    /// likewise a group without any rations isn't an elf, but the last group is,
+    /// even without a separator after it.
+    /// With `comma_rations`, each ration is split on commas too; a ration that isn't a number
+    /// is handled according to the policy, with errors naming the line it's on.
+    fn try_from_with_seps(
+        calories: &str,
+        separators: Separators<'_>,
+        options: ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let Separators { ration, group } = separators;
+        let mut elves = Vec::new();
+        // the line of the last ration parsed, and the offset in the input it was counted up to
+        let (mut line, mut counted) = (1, 0);
+
+        for group in calories.split(group) {
+            let mut rations = Vec::new();
+            let tokens = group.split(ration).flat_map(|text| {
+                let tokens: Vec<&str> = if options.comma_rations {
+                    text.split(',').collect()
+                } else {
+                    vec![text]
+                };
+                tokens.into_iter().map(str::trim)
+            });
+            for token in tokens.filter(|token| !token.is_empty()) {
+                // every token is a slice of the input, so its offset is the distance between them
+                let offset = token.as_ptr() as usize - calories.as_ptr() as usize;
+                line += calories[counted..offset].matches('\n').count();
+                counted = offset;
+                rations.extend(Ration::parse(token, line, options.on_error)?);
+            }
+
+            if !rations.is_empty() {
+                elves.push(Elf { rations });
+            }
+        }
+
+        Ok(Self {
+            elves,
+            sorted: OnceCell::new(),
+        })
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Elf {
     rations: Vec<Ration>,
 }
 
+impl Elf {
+    fn total(&self) -> u64 {
+        self.rations.iter().map(Ration::calories).sum()
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Ration {
     calories: u64,
 }
 
+impl Ration {
+    const fn calories(&self) -> u64 {
+        self.calories
+    }
+
+    /// Parse a ration on the 1-based `line`, or if it isn't a number,
+    /// handle it according to the policy, returning [`None`] for a ration to skip.
+    fn parse(token: &str, line: usize, on_error: ErrorPolicy) -> Result<Option<Self>, ParseError> {
+        let calories = match (token.parse::<u64>(), on_error) {
+            (Ok(calories), _) => calories,
+            (Err(_), ErrorPolicy::Zero) => 0,
+            (Err(error), policy) => {
+                let error = ParseError {
+                    line,
+                    text: token.to_owned(),
+                    error,
+                };
+                if let ErrorPolicy::Fail = policy {
+                    return Err(error);
+                }
+                input::note(format_args!("skipping {error}"));
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(Self { calories }))
+    }
+}
+
 #[derive(Debug)]
-struct ParseError(ParseIntError);
+struct ParseError {
+    /// The 1-based number of the line that isn't an integer.
+    line: usize,
+    text: String,
+    error: ParseIntError,
+}
 
 impl Error for ParseError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        Some(&self.0)
+        Some(&self.error)
     }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "expected an integer; only digits and newlines are valid input"
-        )
+        let Self { line, text, .. } = self;
+        write!(f, "line {line}: expected an integer, found '{text}'")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(elves: &Elves) -> Vec<u64> {
+        elves.iter().collect()
+    }
+
+    #[test]
+    fn last_elf_is_parsed_without_a_trailing_empty_line() {
+        let elves: Elves = "1\n2\n\n3\n4\n".parse().unwrap();
+        assert_eq!(totals(&elves), [3, 7]);
+
+        let elves: Elves = "1\n2\n\n3\n4".parse().unwrap();
+        assert_eq!(totals(&elves), [3, 7]);
+
+        let elves: Elves = "1\n2\n\n3\n4\n\n".parse().unwrap();
+        assert_eq!(totals(&elves), [3, 7]);
+    }
+
+    #[test]
+    fn parse_errors_name_the_line_that_isnt_a_number() {
+        let error = "1\n2\n\n12x\n3\n".parse::<Elves>().err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (4, "12x"));
+        assert_eq!(
+            error.to_string(),
+            "line 4: expected an integer, found '12x'"
+        );
+
+        let options = ParseOptions {
+            separators: Some(Separators::new(";", "||").unwrap()),
+            ..ParseOptions::default()
+        };
+        let error = Elves::parse("1;2||\n3;\n\n4;12x", options).err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (4, "12x"));
+    }
+
+    /// Returns the number of rations of each elf, and their calorie sums.
+    fn parse_with_policy(on_error: ErrorPolicy) -> Result<(Vec<usize>, Vec<u64>), ParseError> {
+        let options = ParseOptions {
+            on_error,
+            ..ParseOptions::default()
+        };
+        let elves = Elves::parse("1\n2\n\nabc\n\n3\n", options)?;
+        Ok((elves.groups().map(<[_]>::len).collect(), totals(&elves)))
+    }
+
+    #[test]
+    fn fail_policy_fails_on_a_line_that_isnt_a_number() {
+        let error = parse_with_policy(ErrorPolicy::Fail).unwrap_err();
+        assert_eq!(error.line, 4);
+    }
+
+    #[test]
+    fn skip_policy_leaves_out_a_line_that_isnt_a_number() {
+        // the elf of only that line has no rations left, so it isn't an elf
+        let (rations, totals) = parse_with_policy(ErrorPolicy::Skip).unwrap();
+        assert_eq!((rations, totals), (vec![2, 1], vec![3, 3]));
+    }
+
+    #[test]
+    fn elves_of_only_skipped_lines_are_left_out_anywhere() {
+        let options = ParseOptions {
+            on_error: ErrorPolicy::Skip,
+            ..ParseOptions::default()
+        };
+        for calories in ["x\n\n1\n\n2\n", "1\n\nx\n\n2\n", "1\n\n2\n\nx\n"] {
+            let elves = Elves::parse(calories, options).unwrap();
+            assert_eq!(elves.min_calories(), Some(1), "{calories:?}");
+            assert_eq!(elves.elves.len(), 2, "{calories:?}");
+        }
+    }
+
+    #[test]
+    fn extra_empty_lines_dont_make_elves() {
+        let elves: Elves = "\n1\n\n\n\n2\n\n".parse().unwrap();
+        assert_eq!(totals(&elves), [1, 2]);
+    }
+
+    #[test]
+    fn zero_policy_counts_a_line_that_isnt_a_number_as_zero() {
+        let (rations, totals) = parse_with_policy(ErrorPolicy::Zero).unwrap();
+        assert_eq!((rations, totals), (vec![2, 1, 1], vec![3, 0, 3]));
+    }
+
+    #[test]
+    fn top_elves_are_ordered_by_sum_then_index() {
+        let elves: Elves = "5\n\n1\n\n5\n\n3\n\n5\n".parse().unwrap();
+        assert_eq!(elves.top_n_indices(2), [0, 2]);
+        assert_eq!(elves.top_n_indices(4), [0, 2, 4, 3]);
+        assert_eq!(elves.top_n_indices(9), [0, 2, 4, 3, 1]);
+        assert!(elves.top_n_indices(0).is_empty());
+        assert_eq!(elves.sum_calories_top::<3>(), 15);
+    }
+
+    #[test]
+    fn percentiles_are_of_the_nearest_rank() {
+        let elves: Elves = "5\n\n2\n\n8\n\n1\n\n7\n\n3\n\n6\n\n4\n".parse().unwrap();
+        assert_eq!(elves.median(), Some(4));
+        assert_eq!(elves.percentile(90.0), Some(8));
+        assert_eq!(elves.percentile(75.0), Some(6));
+        assert_eq!(elves.percentile(0.0), Some(1));
+        assert_eq!(elves.percentile(100.0), Some(8));
+        assert_eq!(elves.totals_sorted(), [8, 7, 6, 5, 4, 3, 2, 1]);
+
+        let elves: Elves = "3\n\n1\n\n2\n".parse().unwrap();
+        assert_eq!(elves.median(), Some(2));
+
+        let elves: Elves = "".parse().unwrap();
+        assert_eq!((elves.median(), elves.percentile(90.0)), (None, None));
+    }
+
+    #[test]
+    fn kth_largest_ranks_from_the_largest() {
+        let elves: Elves = "5\n\n1\n\n5\n\n3\n".parse().unwrap();
+        assert_eq!(elves.kth_largest(1), Some(5));
+        assert_eq!(elves.kth_largest(2), Some(5));
+        assert_eq!(elves.kth_largest(3), Some(3));
+        assert_eq!(elves.kth_largest(4), Some(1));
+        assert_eq!(elves.kth_largest(5), None);
+        assert_eq!(elves.kth_largest(0), None);
+    }
+
+    #[test]
+    fn histogram_of_equal_sums_is_a_single_bucket() {
+        let elves: Elves = "3\n\n1\n2\n\n3\n".parse().unwrap();
+        let histogram = elves.histogram(DEFAULT_BUCKETS);
+        let buckets: Vec<_> = histogram
+            .iter()
+            .map(|bucket| (bucket.start, bucket.end, bucket.elves))
+            .collect();
+        assert_eq!(buckets, [(3, 3, 3)]);
+
+        let elves: Elves = "".parse().unwrap();
+        assert!(elves.histogram(DEFAULT_BUCKETS).is_empty());
+    }
+
+    #[test]
+    fn min_of_a_single_elf_is_its_sum() {
+        let elves: Elves = "1000\n2000\n".parse().unwrap();
+        assert_eq!(elves.min_calories(), Some(3000));
+    }
+
+    #[test]
+    fn min_of_no_elves_is_none() {
+        let elves: Elves = "".parse().unwrap();
+        assert_eq!(elves.min_calories(), None);
+    }
+
+    #[test]
+    fn blanks_separate_elves_by_runs_of_empty_lines() {
+        let options = ParseOptions {
+            separator_blanks: NonZeroUsize::new(2).unwrap(),
+            ..ParseOptions::default()
+        };
+        let elves = Elves::parse("1\n2\n\n3\n\n\n4\n5\n\n\n6\n", options).unwrap();
+        assert_eq!(totals(&elves), [6, 9, 6]);
+    }
+
+    #[test]
+    fn comma_separated_rations_mix_with_a_ration_per_line() {
+        let options = ParseOptions {
+            comma_rations: true,
+            ..ParseOptions::default()
+        };
+        let elves = Elves::parse("1,2,3\n4\n\n5\n6, 7 ,\n\n 8 ,,9,\n", options).unwrap();
+        assert_eq!(
+            elves.groups().map(<[_]>::len).collect::<Vec<_>>(),
+            [4, 3, 2]
+        );
+        assert_eq!(totals(&elves), [10, 18, 17]);
+
+        let error = Elves::parse("1\n\n2,x\n", options).err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (3, "x"));
+    }
+
+    #[test]
+    fn separators_split_rations_and_elves() {
+        let options = ParseOptions {
+            separators: Some(Separators::new(";", "||").unwrap()),
+            ..ParseOptions::default()
+        };
+        let elves = Elves::parse("1;2||3;4", options).unwrap();
+        assert_eq!(totals(&elves), [3, 7]);
+
+        let elves = Elves::parse("1;2||3;4\n", options).unwrap();
+        assert_eq!(totals(&elves), [3, 7]);
     }
 }
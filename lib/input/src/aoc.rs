@@ -0,0 +1,97 @@
+//! Puzzle input fetched from adventofcode.com, available with the `aoc` feature.
+//!
+//! Input is cached once fetched, so later runs read it without the feature.
+
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+/// The environment variable holding the session cookie of a logged in browser.
+#[cfg(feature = "aoc")]
+pub(crate) const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The year of puzzles fetched when none is given.
+pub(crate) const DEFAULT_YEAR: u16 = 2022;
+
+/// Returns the path of the cached input for the day, fetching it first if it isn't cached.
+///
+/// Input is cached in `$XDG_CACHE_HOME/advent-of-code`, or `~/.cache/advent-of-code`,
+/// falling back to the temporary directory if neither is set.
+///
+/// # Errors
+///
+/// If the input isn't cached and can't be fetched, an error is returned.
+/// Without the `aoc` feature, fetching fails with an error of kind [`io::ErrorKind::Unsupported`].
+pub(crate) fn cached(year: u16, day: u8) -> io::Result<PathBuf> {
+    let directory = cache_directory();
+    let path = directory.join(format!("{year}-day-{day}.txt"));
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    let input = fetch(year, day)?;
+
+    // write to a temporary file first, so an interrupted write is never mistaken for a cache hit
+    fs::create_dir_all(&directory)?;
+    let partial = path.with_extension("partial");
+    fs::write(&partial, input)?;
+    fs::rename(&partial, &path)?;
+
+    Ok(path)
+}
+
+fn cache_directory() -> PathBuf {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    cache.join("advent-of-code")
+}
+
+/// Returns the session cookie, or an error explaining how to set it.
+#[cfg(feature = "aoc")]
+fn session() -> io::Result<String> {
+    match env::var(SESSION_VAR) {
+        Ok(session) if !session.trim().is_empty() => Ok(session.trim().to_owned()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "set {SESSION_VAR} to the value of the 'session' cookie \
+                from a browser logged in to adventofcode.com"
+            ),
+        )),
+    }
+}
+
+#[cfg(feature = "aoc")]
+fn fetch(year: u16, day: u8) -> io::Result<String> {
+    let session = session()?;
+    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
+    let response = ureq::get(&url)
+        .set("Cookie", &format!("session={session}"))
+        .set(
+            "User-Agent",
+            "github.com/abysssol/advent-of-code-2022 input crate",
+        )
+        .call();
+
+    match response {
+        Ok(response) => response.into_string(),
+        Err(ureq::Error::Status(400 | 401, _)) => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("the session in {SESSION_VAR} was rejected; it may have expired"),
+        )),
+        Err(ureq::Error::Status(404, _)) => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the puzzle isn't available; it may not be unlocked yet",
+        )),
+        Err(error) => Err(io::Error::other(error)),
+    }
+}
+
+#[cfg(not(feature = "aoc"))]
+fn fetch(_year: u16, _day: u8) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "fetching input requires the 'aoc' feature",
+    ))
+}
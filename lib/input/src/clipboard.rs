@@ -0,0 +1,38 @@
+//! Input read from the system clipboard, as passed with `--clipboard`,
+//! available with the `clipboard` feature.
+//!
+//! The clipboard is read with `arboard`, which supports Windows, macOS, and Linux with X11.
+//! On Wayland, the clipboard is only reachable through XWayland,
+//! and without a display, as in ssh sessions or CI, there's no clipboard to read.
+
+use std::io;
+
+/// Returns the text on the clipboard.
+///
+/// # Errors
+///
+/// If the clipboard can't be reached, an error is returned,
+/// of kind [`io::ErrorKind::InvalidData`] if it holds something other than text.
+/// Without the `clipboard` feature, an error of kind [`io::ErrorKind::Unsupported`] is returned.
+#[cfg(feature = "clipboard")]
+pub(crate) fn read() -> io::Result<String> {
+    use arboard::{Clipboard, Error};
+
+    let text = Clipboard::new().and_then(|mut clipboard| clipboard.get_text());
+    text.map_err(|error| match error {
+        Error::ContentNotAvailable => io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the clipboard doesn't hold any text",
+        ),
+        Error::ClipboardNotSupported => io::Error::new(io::ErrorKind::Unsupported, error),
+        error => io::Error::other(error),
+    })
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub(crate) fn read() -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading the clipboard requires the 'clipboard' feature",
+    ))
+}
@@ -0,0 +1,199 @@
+//! Whether to color help, version, and error output.
+
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{FlagError, Flags, COLOR as COLOR_FLAG, NO_COLOR};
+
+/// When to color output with ANSI escape codes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Color output written to a terminal,
+    /// unless the `NO_COLOR` environment variable is set, as described in [`ColorChoice::resolve`].
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// The process wide color choice, stored as the discriminant of a [`ColorChoice`].
+static COLOR: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Set when to color help, version, and error output for the whole process.
+///
+/// This can be called before any of the `with` functions to force color on or off,
+/// for example in an environment where ANSI escape codes aren't understood.
+/// If `--color` or `--no-color` is passed on the command line, it takes precedence over this choice.
+pub fn set_color(choice: ColorChoice) {
+    COLOR.store(choice as u8, Ordering::Relaxed);
+}
+
+/// Returns the process wide color choice, as set with [`set_color`] or `--color`.
+pub fn color() -> ColorChoice {
+    match COLOR.load(Ordering::Relaxed) {
+        1 => ColorChoice::Always,
+        2 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+impl ColorChoice {
+    /// Returns the color choice passed on the command line, either with `--color`,
+    /// or with `--no-color` as a shorthand for `--color never`.
+    /// ```
+    /// use input::{Args, ColorChoice, Description};
+    ///
+    /// let parse = |args: &[&str]| {
+    ///     let description = Description {
+    ///         name: "app",
+    ///         bin_name: "app".into(),
+    ///         description: "",
+    ///         version: (0, 0, 0),
+    ///         flags: &[],
+    ///         standalone_flags: &[],
+    ///         env_vars: &[],
+    ///         examples: &[],
+    ///     };
+    ///     let args = ["app", "--stdin"].iter().chain(args).map(|&arg| arg.to_owned());
+    ///     Args::from_args(args, description)
+    /// };
+    ///
+    /// let args = parse(&["--no-color"]).unwrap();
+    /// assert_eq!(ColorChoice::from_flags(&args.flags).unwrap(), Some(ColorChoice::Never));
+    ///
+    /// let args = parse(&["--color", "always"]).unwrap();
+    /// assert_eq!(ColorChoice::from_flags(&args.flags).unwrap(), Some(ColorChoice::Always));
+    ///
+    /// let args = parse(&[]).unwrap();
+    /// assert_eq!(ColorChoice::from_flags(&args.flags).unwrap(), None);
+    ///
+    /// assert!(parse(&["--no-color", "--color", "always"]).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the value of `--color` isn't a color choice, then [`FlagError`] is returned.
+    pub fn from_flags(flags: &Flags) -> Result<Option<Self>, FlagError> {
+        if flags.is_set(NO_COLOR.name) {
+            return Ok(Some(Self::Never));
+        }
+        flags.parse(COLOR_FLAG.name)
+    }
+
+    /// Returns the choice with [`ColorChoice::Auto`] turned off if the `NO_COLOR`
+    /// environment variable is set to anything but an empty string, following
+    /// <https://no-color.org>.
+    ///
+    /// Only the automatic choice gives way to `NO_COLOR`;
+    /// an explicit `--color always` still colors output, as the convention recommends.
+    /// ```
+    /// use input::ColorChoice;
+    /// use std::env;
+    ///
+    /// env::remove_var("NO_COLOR");
+    /// assert_eq!(ColorChoice::Auto.resolve(), ColorChoice::Auto);
+    ///
+    /// env::set_var("NO_COLOR", "");
+    /// assert_eq!(ColorChoice::Auto.resolve(), ColorChoice::Auto);
+    ///
+    /// env::set_var("NO_COLOR", "1");
+    /// assert_eq!(ColorChoice::Auto.resolve(), ColorChoice::Never);
+    /// assert_eq!(ColorChoice::Always.resolve(), ColorChoice::Always);
+    /// ```
+    pub fn resolve(self) -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        match self {
+            Self::Auto if no_color => Self::Never,
+            _ => self,
+        }
+    }
+
+    /// Returns `true` if output written to the stream should be colored.
+    pub(crate) fn enabled(self, stream: &impl IsTerminal) -> bool {
+        match self.resolve() {
+            Self::Auto => stream.is_terminal(),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = UnknownColorChoice;
+
+    fn from_str(choice: &str) -> Result<Self, Self::Err> {
+        match choice {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(UnknownColorChoice),
+        }
+    }
+}
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let choice = match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Never => "never",
+        };
+        f.write_str(choice)
+    }
+}
+
+/// An error returned when parsing an unknown color choice.
+#[derive(Debug, Clone, Copy)]
+pub struct UnknownColorChoice;
+
+impl Error for UnknownColorChoice {}
+
+impl Display for UnknownColorChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "expected one of 'auto', 'always', or 'never'")
+    }
+}
+
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// Make the section headings of help information, and the app name of version information, bold.
+pub(crate) fn paint_help(help: &str) -> String {
+    let mut lines = help.lines();
+    let mut painted = String::with_capacity(help.len());
+
+    // both help and version information start with the app name and version
+    if let Some(first) = lines.next() {
+        match first.split_once(' ') {
+            Some((name, version)) => painted.push_str(&format!("{BOLD}{name}{RESET} {version}")),
+            None => painted.push_str(first),
+        }
+    }
+
+    for line in lines {
+        painted.push('\n');
+        let is_heading = line.ends_with(':') && !line.starts_with(' ');
+        if is_heading {
+            painted.push_str(&format!("{BOLD}{line}{RESET}"));
+        } else if let Some(usage) = line.strip_prefix("Usage:") {
+            painted.push_str(&format!("{BOLD}Usage:{RESET}{usage}"));
+        } else {
+            painted.push_str(line);
+        }
+    }
+
+    painted
+}
+
+/// Make the `error:` prefix of error output bold and red.
+pub(crate) fn paint_error(error: &str) -> String {
+    match error.strip_prefix("error:") {
+        Some(rest) => format!("{BOLD_RED}error:{RESET}{rest}"),
+        None => error.to_owned(),
+    }
+}
@@ -0,0 +1,210 @@
+//! Decompression of input, available with the `compression` feature.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The format to decompress input from before decoding it as text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Input is read as is.
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    /// The format is detected from the leading bytes of input, with [`Compression::detect`].
+    Auto,
+}
+
+/// The leading bytes of a gzip stream.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// The leading bytes of a zstd frame.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// The leading bytes of a bzip2 stream.
+const BZIP2_MAGIC: &[u8] = b"BZh";
+
+impl Compression {
+    /// Returns the format of input starting with `prefix`,
+    /// which is [`Compression::None`] unless it starts with the magic bytes of a known format.
+    ///
+    /// Sniffing the bytes works for piped input too, unlike guessing from a file extension.
+    /// ```
+    /// use input::Compression;
+    ///
+    /// assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08, 0x00]), Compression::Gzip);
+    /// assert_eq!(Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]), Compression::Zstd);
+    /// assert_eq!(Compression::detect(b"BZh9"), Compression::Bzip2);
+    /// assert_eq!(Compression::detect(b"A Y\nB X\n"), Compression::None);
+    /// // too short to be either
+    /// assert_eq!(Compression::detect(&[0x28, 0xb5]), Compression::None);
+    /// assert_eq!(Compression::detect(&[]), Compression::None);
+    /// ```
+    pub fn detect(prefix: &[u8]) -> Self {
+        if prefix.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if prefix.starts_with(ZSTD_MAGIC) {
+            Self::Zstd
+        } else if prefix.starts_with(BZIP2_MAGIC) {
+            Self::Bzip2
+        } else {
+            Self::None
+        }
+    }
+
+    /// Returns the format to read the file at `path` with,
+    /// which for [`Compression::Auto`] is the format its extension names, if any.
+    ///
+    /// Any other format is returned as is, and a file without a known extension is still
+    /// detected from its leading bytes.
+    /// ```
+    /// use input::Compression;
+    /// use std::path::Path;
+    ///
+    /// let auto = |path| Compression::Auto.for_path(Path::new(path));
+    /// assert_eq!(auto("input.txt.gz"), Compression::Gzip);
+    /// assert_eq!(auto("input.txt.zst"), Compression::Zstd);
+    /// assert_eq!(auto("input.txt.bz2"), Compression::Bzip2);
+    /// assert_eq!(auto("input.txt"), Compression::Auto);
+    /// assert_eq!(Compression::None.for_path(Path::new("input.bz2")), Compression::None);
+    /// ```
+    pub fn for_path(self, path: &Path) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst") => Self::Zstd,
+            Some("bz2") => Self::Bzip2,
+            _ => self,
+        }
+    }
+
+    /// Returns `true` if support for this format was compiled in.
+    pub const fn is_supported(self) -> bool {
+        match self {
+            Self::None | Self::Auto => true,
+            Self::Gzip | Self::Zstd | Self::Bzip2 => cfg!(feature = "compression"),
+        }
+    }
+
+    /// Wrap the reader in a decoder for this format.
+    ///
+    /// With [`Compression::Auto`], the leading bytes are read to detect the format,
+    /// then put back in front of the rest, so plain text passes through unchanged.
+    /// ```
+    /// use input::Compression;
+    /// use std::io::{ErrorKind, Read};
+    ///
+    /// let read = |input: &'static [u8]| {
+    ///     let mut text = String::new();
+    ///     let mut reader = Compression::Auto.decoder(input)?;
+    ///     reader.read_to_string(&mut text).map(|_| text)
+    /// };
+    ///
+    /// assert_eq!(read(b"A Y\n").unwrap(), "A Y\n");
+    ///
+    /// // "A Y\n", compressed with gzip
+    /// let gzip = &[
+    ///     0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x73, 0x54,
+    ///     0x88, 0xe4, 0x02, 0x00, 0xa7, 0xa9, 0x0d, 0xb1, 0x04, 0x00, 0x00, 0x00,
+    /// ];
+    /// if Compression::Gzip.is_supported() {
+    ///     assert_eq!(read(gzip).unwrap(), "A Y\n");
+    /// } else {
+    ///     assert_eq!(read(gzip).unwrap_err().kind(), ErrorKind::Unsupported);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If support for this format wasn't compiled in, an error of kind
+    /// [`io::ErrorKind::Unsupported`] is returned,
+    /// otherwise any error from reading the leading bytes or initializing the decoder.
+    pub fn decoder<'a>(self, reader: impl BufRead + 'a) -> io::Result<Box<dyn BufRead + 'a>> {
+        if let Self::Auto = self {
+            let mut reader = reader;
+            let mut prefix = Vec::with_capacity(ZSTD_MAGIC.len());
+            (&mut reader)
+                .take(ZSTD_MAGIC.len() as u64)
+                .read_to_end(&mut prefix)?;
+
+            let format = Self::detect(&prefix);
+            return format.wrap(io::Cursor::new(prefix).chain(reader));
+        }
+
+        self.wrap(reader)
+    }
+
+    /// Wrap the reader in a decoder for this format, which must already be known.
+    fn wrap<'a>(self, reader: impl BufRead + 'a) -> io::Result<Box<dyn BufRead + 'a>> {
+        match self {
+            Self::None | Self::Auto => Ok(Box::new(reader)),
+            #[cfg(feature = "compression")]
+            Self::Gzip => Ok(Box::new(io::BufReader::new(
+                flate2::bufread::MultiGzDecoder::new(reader),
+            ))),
+            #[cfg(feature = "compression")]
+            Self::Zstd => Ok(Box::new(io::BufReader::new(
+                zstd::stream::read::Decoder::with_buffer(reader)?,
+            ))),
+            #[cfg(feature = "compression")]
+            Self::Bzip2 => Ok(Box::new(io::BufReader::new(
+                bzip2::bufread::MultiBzDecoder::new(reader),
+            ))),
+            #[cfg(not(feature = "compression"))]
+            Self::Gzip | Self::Zstd | Self::Bzip2 => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{self} support requires the 'compression' feature"),
+            )),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = UnknownCompression;
+
+    fn from_str(compression: &str) -> Result<Self, Self::Err> {
+        match compression {
+            "none" => Ok(Self::None),
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            "bzip2" => Ok(Self::Bzip2),
+            "auto" => Ok(Self::Auto),
+            _ => Err(UnknownCompression),
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let compression = match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::Bzip2 => "bzip2",
+            Self::Auto => "auto",
+        };
+        f.write_str(compression)
+    }
+}
+
+/// An error returned when parsing an unknown compression format.
+#[derive(Debug, Clone, Copy)]
+pub struct UnknownCompression;
+
+impl Error for UnknownCompression {}
+
+impl Display for UnknownCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected one of 'gzip', 'zstd', 'bzip2', 'auto', or 'none'"
+        )
+    }
+}
@@ -0,0 +1,114 @@
+//! Rendering of errors for people or for other programs.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write};
+use std::str::FromStr;
+
+use crate::SomeError;
+
+/// How an error is written to stderr when an app fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ErrorFormat {
+    /// The error and its sources pretty printed, as with `{error:#}`.
+    #[default]
+    Text,
+    /// A single line of json, `{"error": "...", "sources": ["...", ...]}`.
+    Json,
+}
+
+impl ErrorFormat {
+    /// Render the error and all of its sources in this format.
+    /// ```
+    /// use input::{ErrorFormat, Input, IoError, SomeError};
+    /// use std::io::{self, ErrorKind};
+    ///
+    /// let error = SomeError::new(IoError {
+    ///     input: Input::Stdin,
+    ///     error: io::Error::new(ErrorKind::Other, "bad \"pipe\""),
+    /// });
+    ///
+    /// assert_eq!(
+    ///     ErrorFormat::Json.render(&error),
+    ///     r#"{"error": "can't read from stdin", "sources": ["bad \"pipe\""]}"#
+    /// );
+    /// assert_eq!(ErrorFormat::Text.render(&error), format!("{error:#}"));
+    /// ```
+    pub fn render(self, error: &SomeError) -> String {
+        match self {
+            Self::Text => format!("{error:#}"),
+            Self::Json => {
+                let mut chain = error.iter();
+                let mut json = String::from("{\"error\": ");
+                push_json_string(&mut json, chain.next().map(ToString::to_string));
+                json.push_str(", \"sources\": [");
+                for (i, source) in chain.enumerate() {
+                    if i > 0 {
+                        json.push_str(", ");
+                    }
+                    push_json_string(&mut json, Some(source.to_string()));
+                }
+                json.push_str("]}");
+
+                json
+            }
+        }
+    }
+}
+
+/// Append the string quoted and escaped as a json string, or `null` if there is none.
+pub(crate) fn push_json_string(json: &mut String, string: Option<String>) {
+    let Some(string) = string else {
+        json.push_str("null");
+        return;
+    };
+
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+impl FromStr for ErrorFormat {
+    type Err = UnknownErrorFormat;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(UnknownErrorFormat),
+        }
+    }
+}
+
+impl Display for ErrorFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let format = match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        };
+        f.write_str(format)
+    }
+}
+
+/// An error returned when parsing an unknown error format.
+#[derive(Debug, Clone, Copy)]
+pub struct UnknownErrorFormat;
+
+impl Error for UnknownErrorFormat {}
+
+impl Display for UnknownErrorFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "expected one of 'text' or 'json'")
+    }
+}
@@ -0,0 +1,38 @@
+//! Input read from an inherited file descriptor, as passed with `--fd`, available on Unix.
+
+use std::fs;
+use std::io;
+
+/// Open a file descriptor inherited from the parent process for reading.
+///
+/// The descriptor is duplicated rather than taken over,
+/// so one that isn't open fails cleanly instead of being closed when the file is dropped.
+///
+/// # Errors
+///
+/// If the descriptor isn't open, an error is returned.
+/// On platforms other than Unix, an error of kind [`io::ErrorKind::Unsupported`] is returned.
+#[cfg(unix)]
+pub(crate) fn open(fd: i32) -> io::Result<fs::File> {
+    use std::os::fd::BorrowedFd;
+
+    if fd < 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a file descriptor can't be negative",
+        ));
+    }
+
+    // SAFETY: the descriptor is only borrowed to duplicate it, which fails if it isn't open,
+    // and nothing in this process closes inherited descriptors while it's borrowed
+    let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+    borrowed.try_clone_to_owned().map(fs::File::from)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn open(_fd: i32) -> io::Result<fs::File> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading a file descriptor is unsupported on this platform",
+    ))
+}
@@ -11,6 +11,10 @@
 //!         bin_name: "<binary-name>".into(),
 //!         description: "<description>",
 //!         version: (0, 0, 0),
+//!         flags: &[],
+//!         standalone_flags: &[],
+//!         env_vars: &[],
+//!         examples: &[],
 //!     },
 //!     |input| {
 //!         // app logic here
@@ -19,200 +23,3775 @@
 //! );
 //! ```
 
+use std::any::Any;
 use std::borrow::Cow;
+use std::char::ParseCharError;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::io::{BufRead, IsTerminal, Read, Write};
 use std::iter::FusedIterator;
-use std::ops::Deref;
-use std::{env, fmt, fs, io, process};
+use std::num::NonZeroUsize;
+use std::num::{ParseFloatError, ParseIntError};
+use std::ops::{Add, Deref};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::{FromStr, ParseBoolError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use std::{env, fmt, fs, io, mem, process};
+
+mod aoc;
+mod clipboard;
+mod color;
+mod compression;
+mod error_format;
+mod fd;
+#[cfg(feature = "memmap")]
+mod mmap;
+mod path;
+mod rng;
+mod watch;
+
+pub use color::{color, set_color, ColorChoice, UnknownColorChoice};
+pub use compression::{Compression, UnknownCompression};
+pub use error_format::{ErrorFormat, UnknownErrorFormat};
+#[cfg(feature = "memmap")]
+pub use mmap::MappedInput;
+pub use rng::SplitMix64;
 
 /// Provides input for advent of code to the provided function.
 ///
-/// Provides a [`String`] with the input collected from standard input or a file,
-/// as specified with command line arguments.
-/// If any errors are encountered, they will be displayed and the app will exit.
-pub fn with(description: Description, main: impl FnOnce(String) -> Result<(), SomeError>) {
-    if let Err(error) = get(description).and_then(main) {
-        eprintln!("{error:#}");
-        process::exit(1);
-    }
-}
-
-/// Returns a [`String`] containing input.
+/// Provides a [`String`] with the input collected from standard input or a file,
+/// as specified with command line arguments.
+/// If any errors are encountered, they will be displayed and the app will exit,
+/// with a code depending on the kind of error; see [`SomeError::exit_code`].
+///
+/// The function can return `()`, or an [`Outcome`] to exit with a code of its choosing
+/// without an error being displayed.
+pub fn with<T: IntoOutcome>(
+    description: Description,
+    main: impl FnOnce(String) -> Result<T, SomeError>,
+) {
+    with_flags(description, |input, _| main(input));
+}
+
+/// How an app finished without an error, as returned by the function provided to [`with`].
+/// ```no_run
+/// use input::Outcome;
+/// # let description = input::Description {
+/// #     name: "", bin_name: "".into(), description: "", version: (0, 0, 0),
+/// #     flags: &[], standalone_flags: &[], env_vars: &[], examples: &[],
+/// # };
+///
+/// input::with(description, |input| {
+///     match input.lines().find(|line| line.starts_with('#')) {
+///         Some(line) => println!("{line}"),
+///         // nothing to print, but scripts can tell from the exit code
+///         None => return Ok(Outcome::Exit(4)),
+///     }
+///     Ok(Outcome::Success)
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The app exits with code 0.
+    #[default]
+    Success,
+    /// The app exits with the code, as when no solution is found, without displaying anything.
+    Exit(u8),
+}
+
+impl Outcome {
+    /// Returns the code the app exits with.
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Exit(code) => code,
+        }
+    }
+}
+
+/// A value returned from a successful app, which decides its exit code.
+///
+/// Returning `()` always exits with 0, while an [`Outcome`] exits with its code.
+pub trait IntoOutcome {
+    fn into_outcome(self) -> Outcome;
+}
+
+impl IntoOutcome for () {
+    fn into_outcome(self) -> Outcome {
+        Outcome::Success
+    }
+}
+
+impl IntoOutcome for Outcome {
+    fn into_outcome(self) -> Outcome {
+        self
+    }
+}
+
+/// Provides input for advent of code to the provided function, then returns the code to exit with.
+///
+/// Like [`with`], but instead of exiting on an error, the error is displayed
+/// and its [`SomeError::exit_code`] is returned, so the app can exit on its own terms.
+/// Without an error, the code of the [`Outcome`] is returned.
+/// ```no_run
+/// use input::Description;
+/// use std::process::ExitCode;
+///
+/// fn main() -> ExitCode {
+///     input::with_exit_code(
+///         Description {
+///             name: "app",
+///             bin_name: "app".into(),
+///             description: "An app",
+///             version: (1, 0, 0),
+///             flags: &[],
+///             standalone_flags: &[],
+///             env_vars: &[],
+///             examples: &[],
+///         },
+///         |input| {
+///             println!("{}", input.lines().count());
+///             Ok(())
+///         },
+///     )
+/// }
+/// ```
+pub fn with_exit_code<T: IntoOutcome>(
+    description: Description,
+    main: impl FnOnce(String) -> Result<T, SomeError>,
+) -> ExitCode {
+    let result = try_run(description, env::args(), |args| {
+        run_flags(args, |input, _| main(input))
+    });
+    ExitCode::from(report(result))
+}
+
+/// Provides input for advent of code to the provided function, using the given arguments.
+///
+/// Like [`with`], but the arguments are taken from `args` instead of [`env::args`],
+/// with the binary name first, and any error is returned instead of displayed.
+/// Help and version information aren't displayed either; they're returned as a [`NoInput`] error.
+///
+/// # Errors
+///
+/// An error is returned if the arguments are invalid, if input can't be read,
+/// or if the provided function fails; otherwise its [`Outcome`] is returned.
+/// ```
+/// use input::{Description, NoInput};
+///
+/// let description = Description {
+///     name: "app",
+///     bin_name: "app".into(),
+///     description: "An app",
+///     version: (1, 0, 0),
+///     flags: &[],
+///     standalone_flags: &[],
+///     env_vars: &[],
+///     examples: &[],
+/// };
+/// let args = ["app", "--help"].map(String::from).into_iter();
+///
+/// let error = input::with_with_args(description, args, |_| Ok(())).unwrap_err();
+/// assert!(matches!(error.0.downcast_ref(), Some(NoInput::Help(_))));
+/// ```
+///
+/// The function is only run once, so `--split` isn't supported;
+/// use [`with_output`] to solve each segment of input separately:
+/// ```
+/// # use input::Description;
+/// # let description = Description {
+/// #     name: "app", bin_name: "app".into(), description: "", version: (0, 0, 0),
+/// #     flags: &[], standalone_flags: &[], env_vars: &[], examples: &[],
+/// # };
+/// let args = ["app", "--split", "---", "--stdin"].map(String::from);
+/// let error = input::with_with_args(description, args.into_iter(), |_| Ok(())).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "'--split' isn't supported by this app, as it solves all of its input at once"
+/// );
+/// ```
+pub fn with_with_args<T: IntoOutcome>(
+    description: Description,
+    args: impl Iterator<Item = String>,
+    main: impl FnOnce(String) -> Result<T, SomeError>,
+) -> Result<Outcome, SomeError> {
+    try_run(description, args, |args| {
+        run_flags(args, |input, _| main(input))
+    })
+    .map_err(|(error, _)| error)
+}
+
+/// Provides input and app specific flags for advent of code to the provided function.
+///
+/// Like [`with`], but also passes along any [`Flags`] declared in [`Description::flags`]
+/// that were found on the command line.
+pub fn with_flags<T: IntoOutcome>(
+    description: Description,
+    main: impl FnOnce(String, &Flags) -> Result<T, SomeError>,
+) {
+    run(description, |args| run_flags(args, main));
+}
+
+/// Read input and pass it, along with the flags, to the function, as for [`with_flags`].
+fn run_flags<T: IntoOutcome>(
+    args: Args,
+    main: impl FnOnce(String, &Flags) -> Result<T, SomeError>,
+) -> Result<Outcome, SomeError> {
+    let Args {
+        input,
+        flags,
+        standalone,
+    } = args;
+
+    if flags.is_set(REPEAT.name) {
+        return Err(SomeError::new(Message(
+            "'--repeat' isn't supported by this app, as it prints its own output",
+        )));
+    }
+
+    if flags.is_set(WATCH.name) {
+        return Err(SomeError::new(Message(
+            "'--watch' isn't supported by this app, as it can only run once",
+        )));
+    }
+
+    if flags.is_set(SPLIT.name) {
+        return Err(SomeError::new(UNSUPPORTED_SPLIT));
+    }
+
+    if flags.is_set(NO_TRAILING_NEWLINE.name) {
+        return Err(SomeError::new(Message(
+            "'--no-trailing-newline' isn't supported by this app, as it prints its own output",
+        )));
+    }
+
+    if flags.is_set(CHECK.name) {
+        return Err(SomeError::new(UNSUPPORTED_CHECK));
+    }
+
+    if flags.is_set(PART.name) {
+        return Err(SomeError::new(UNSUPPORTED_PART));
+    }
+
+    if flags.is_set(PREFIX.name) {
+        return Err(SomeError::new(UNSUPPORTED_PREFIX));
+    }
+
+    if flags.is_set(GROUP_DIGITS.name) {
+        return Err(SomeError::new(UNSUPPORTED_GROUP_DIGITS));
+    }
+
+    let profile = flags.is_set(PROFILE.name);
+    let (input, read_duration) = if standalone {
+        (String::new(), None)
+    } else {
+        let (input, duration) = timed(profile, || read(input, &flags));
+        (input?, duration)
+    };
+    let size = profile.then(|| input.size());
+
+    let time = flags.is_set(TIME.name);
+    let (result, duration) = timed(time || profile, || main(input, &flags));
+    if time {
+        report_durations(duration.as_slice());
+    }
+    if let Some(size) = size {
+        report_profile(size, read_duration, duration.as_slice());
+    }
+
+    result.map(IntoOutcome::into_outcome)
+}
+
+/// Provides input and app specific flags to the provided function, then prints its output.
+///
+/// Like [`with_flags`], but the function borrows the input and returns its output,
+/// so it can be run several times on the same input, as requested with `--repeat`.
+/// Only the output of the last run is printed, followed by a newline
+/// unless `--no-trailing-newline` is passed.
+/// For output of several lines, that only affects the last one;
+/// newlines within the output are always printed.
+///
+/// With `--split`, the input is divided into segments with [`split_segments`],
+/// and each is solved on its own, with its output printed after a line labeling it,
+/// as in `Segment 1:`.
+/// Input of several files is divided file by file, and each segment is solved as a single file.
+pub fn with_output<T: Display>(
+    description: Description,
+    main: impl FnMut(&str, &Flags) -> Result<T, SomeError>,
+) {
+    with_validated(
+        description,
+        |_, _| Err(SomeError::new(UNSUPPORTED_CHECK)),
+        main,
+    );
+}
+
+/// Like [`with_output`], but with `--check`, only runs the validation function.
+///
+/// The validation function should just confirm the input can be parsed,
+/// without doing the potentially expensive work of solving.
+/// If it succeeds the app exits successfully, otherwise the error is displayed.
+/// For an app parsing its input with [`FromStr`], that can be as simple as:
+/// ```no_run
+/// # struct Puzzle;
+/// # impl std::str::FromStr for Puzzle {
+/// #     type Err = std::num::ParseIntError;
+/// #     fn from_str(s: &str) -> Result<Self, Self::Err> { s.parse::<u8>().map(|_| Puzzle) }
+/// # }
+/// # let description = input::Description {
+/// #     name: "", bin_name: "".into(), description: "", version: (0, 0, 0),
+/// #     flags: &[], standalone_flags: &[], env_vars: &[], examples: &[],
+/// # };
+/// input::with_validated(
+///     description,
+///     |input, _| {
+///         input.parse::<Puzzle>()?;
+///         Ok(())
+///     },
+///     |input, _| {
+///         let puzzle = input.parse::<Puzzle>()?;
+///         // solve the puzzle here
+///         Ok("<answer>")
+///     },
+/// );
+/// ```
+pub fn with_validated<T: Display>(
+    description: Description,
+    validate: impl FnOnce(&str, &Flags) -> Result<(), SomeError>,
+    mut main: impl FnMut(&str, &Flags) -> Result<T, SomeError>,
+) {
+    solve(
+        description,
+        read,
+        |input: &String, flags| validate(input, flags),
+        |input, flags| {
+            if flags.is_set(PART.name) {
+                return Err(SomeError::new(UNSUPPORTED_PART));
+            }
+            if flags.is_set(PREFIX.name) {
+                return Err(SomeError::new(UNSUPPORTED_PREFIX));
+            }
+            if flags.is_set(GROUP_DIGITS.name) {
+                return Err(SomeError::new(UNSUPPORTED_GROUP_DIGITS));
+            }
+            main(input, flags)
+        },
+    );
+}
+
+/// Like [`with_validated`], but the solution tells apart the answers to each part of the puzzle.
+///
+/// Both answers are printed on their own lines,
+/// or just one of them if selected with `--part`.
+///
+/// With `--prefix`, each answer printed is labeled with its part, as in `Part 1: 24000`.
+/// The labels are part of the output on stdout, so they're kept with `--quiet`,
+/// which only silences notes on stderr.
+/// With `--group-digits`, answers that are whole numbers are grouped with [`group_digits`].
+/// Output other than answers is printed as is.
+/// ```no_run
+/// use input::Answers;
+/// # let description = input::Description {
+/// #     name: "", bin_name: "".into(), description: "", version: (0, 0, 0),
+/// #     flags: &[], standalone_flags: &[], env_vars: &[], examples: &[],
+/// # };
+///
+/// input::with_parts(
+///     description,
+///     |_, _| Ok(()),
+///     |input, _| Ok(Answers::parts(input.len(), input.lines().count())),
+/// );
+/// ```
+pub fn with_parts(
+    description: Description,
+    validate: impl FnOnce(&str, &Flags) -> Result<(), SomeError>,
+    mut main: impl FnMut(&str, &Flags) -> Result<Answers, SomeError>,
+) {
+    solve(
+        description,
+        read,
+        |input: &String, flags| validate(input, flags),
+        |input, flags| main(input, flags)?.select(flags),
+    );
+}
+
+/// Like [`with_parts`], but the functions get the contents of each file of input separately.
+///
+/// Input from a single file or stdin is passed as the only element.
+pub fn with_each_file(
+    description: Description,
+    validate: impl FnOnce(&[InputFile], &Flags) -> Result<(), SomeError>,
+    mut main: impl FnMut(&[InputFile], &Flags) -> Result<Answers, SomeError>,
+) {
+    solve(
+        description,
+        read_each,
+        |files: &Vec<InputFile>, flags| validate(files, flags),
+        |files, flags| main(files, flags)?.select(flags),
+    );
+}
+
+/// The output of a solution run with [`with_parts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answers {
+    /// The answers to the first and second part of the puzzle.
+    Parts(String, String),
+    /// Any other output, like a table; no part of it can be selected with `--part`.
+    Other(String),
+}
+
+impl Answers {
+    pub fn parts(one: impl Display, two: impl Display) -> Self {
+        Self::Parts(one.to_string(), two.to_string())
+    }
+
+    pub fn other(output: impl Display) -> Self {
+        Self::Other(output.to_string())
+    }
+
+    /// Returns the output to print, with just the answer to the part if one is given,
+    /// and each answer grouped into thousands and labeled with its part if requested.
+    fn select(self, flags: &Flags) -> Result<String, SomeError> {
+        let part = flags.parse::<Part>(PART.name)?;
+        let prefix = flags.is_set(PREFIX.name);
+        let group = flags.is_set(GROUP_DIGITS.name);
+
+        let label = |part: &str, answer: String| {
+            let answer = match group_digit_str(&answer) {
+                Some(grouped) if group => grouped,
+                _ => answer,
+            };
+            if prefix {
+                format!("Part {part}: {answer}")
+            } else {
+                answer
+            }
+        };
+
+        match (part, self) {
+            (None, Self::Parts(one, two)) => {
+                Ok(format!("{}\n{}", label("1", one), label("2", two)))
+            }
+            (None, Self::Other(output)) => Ok(output),
+            (Some(Part::One), Self::Parts(one, _)) => Ok(label("1", one)),
+            (Some(Part::Two), Self::Parts(_, two)) => Ok(label("2", two)),
+            (Some(_), Self::Other(_)) => Err(SomeError::new(Message(
+                "'--part' can't select an answer from this output",
+            ))),
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parts(one, two) => write!(f, "{one}\n{two}"),
+            Self::Other(output) => f.write_str(output),
+        }
+    }
+}
+
+/// A part of the puzzle, as selected with `--part`.
+#[derive(Debug, Clone, Copy)]
+enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = Message;
+
+    fn from_str(part: &str) -> Result<Self, Self::Err> {
+        match part {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            _ => Err(Message("expected '1' or '2'")),
+        }
+    }
+}
+
+/// Format a number with a comma between each group of three digits, as in `1,000,000`.
+///
+/// This is how answers are printed with `--group-digits`, which is easier to read for people,
+/// but not for other programs, so answers are printed as plain digits by default.
+/// ```
+/// use input::group_digits;
+///
+/// assert_eq!(group_digits(0), "0");
+/// assert_eq!(group_digits(999), "999");
+/// assert_eq!(group_digits(1000), "1,000");
+/// assert_eq!(group_digits(24000), "24,000");
+/// assert_eq!(group_digits(1_000_000), "1,000,000");
+/// assert_eq!(group_digits(u64::MAX), "18,446,744,073,709,551,615");
+/// ```
+pub fn group_digits(number: u64) -> String {
+    let digits = number.to_string();
+    group_digit_str(&digits).unwrap_or(digits)
+}
+
+/// Group the digits of a non-negative integer of any size, or return [`None`] for other text.
+fn group_digit_str(digits: &str) -> Option<String> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    Some(grouped)
+}
+
+/// Print a note on stderr, unless `--quiet` was passed.
+pub fn note(message: impl Display) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("note: {message}");
+    }
+}
+
+/// Set from `--quiet` before running the app, to be read by [`note`].
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Read input, then validate it or solve it as requested with the builtin flags.
+fn solve<I: Default + Size + Segments, T: Display>(
+    description: Description,
+    read: impl Fn(Input, &Flags) -> Result<I, SomeError>,
+    validate: impl FnOnce(&I, &Flags) -> Result<(), SomeError>,
+    mut main: impl FnMut(&I, &Flags) -> Result<T, SomeError>,
+) {
+    run(description, |args| {
+        let Args {
+            input,
+            flags,
+            standalone,
+        } = args;
+
+        let profile = flags.is_set(PROFILE.name);
+        let read_input = |input| -> Result<_, SomeError> {
+            if standalone {
+                Ok((I::default(), None))
+            } else {
+                let (input, duration) = timed(profile, || read(input, &flags));
+                Ok((input?, duration))
+            }
+        };
+
+        if flags.is_set(CHECK.name) {
+            let (input, _) = read_input(input)?;
+            return validate(&input, &flags).map(|()| Outcome::Success);
+        }
+        let runs = flags.parse::<NonZeroUsize>(REPEAT.name)?;
+        let time = flags.is_set(TIME.name);
+
+        let split = flags.value(SPLIT.name).filter(|_| !standalone);
+        let mut solve_input = |input| -> Result<(), SomeError> {
+            let (input, read_duration) = read_input(input)?;
+            let segments = match split {
+                Some(delimiter) => input.segments(delimiter),
+                None => vec![input],
+            };
+
+            let count = segments.len();
+            for (index, input) in segments.iter().enumerate() {
+                if split.is_some() {
+                    writeln!(io::stdout(), "Segment {}:", index + 1)?;
+                }
+
+                let mut durations = Vec::new();
+                let mut run = || {
+                    let (output, duration) = timed(time || profile, || main(input, &flags));
+                    durations.extend(duration);
+                    output
+                };
+
+                let mut output = run()?;
+                for _ in 1..runs.map_or(1, NonZeroUsize::get) {
+                    output = run()?;
+                }
+
+                if time {
+                    report_durations(&durations);
+                }
+                if profile {
+                    report_profile(input.size(), read_duration, &durations);
+                }
+
+                // only the very last line of output can go without a newline
+                let mut stdout = io::stdout().lock();
+                if flags.is_set(NO_TRAILING_NEWLINE.name) && index + 1 == count {
+                    write!(stdout, "{output}")?;
+                } else {
+                    writeln!(stdout, "{output}")?;
+                }
+                stdout.flush()?;
+            }
+
+            Ok(())
+        };
+
+        if flags.is_set(WATCH.name) {
+            // errors are displayed without exiting, so the next change can fix them
+            let format = flags.parse(ERROR_FORMAT.name)?.unwrap_or_default();
+            let file = input.path().unwrap_or_default().to_owned();
+            return watch::watch(Path::new(&file), || {
+                if let Err(error) = solve_input(input.clone()) {
+                    display_error(&error, format);
+                }
+            })
+            .map(|()| Outcome::Success)
+            .map_err(|error| SomeError::new(error).context(format!("can't watch file '{file}'")));
+        }
+
+        solve_input(input).map(|()| Outcome::Success)
+    });
+}
+
+fn parse_args(
+    description: Description,
+    args: impl Iterator<Item = String>,
+) -> Result<Args, SomeError> {
+    description.validate()?;
+
+    // help may be displayed before parsing finishes, so find the color choice up front;
+    // an invalid choice is reported once parsing does finish
+    let args: Vec<_> = args.collect();
+    let choice = args.windows(2).rev().find(|pair| pair[0] == "--color");
+    if let Some(Ok(choice)) = choice.map(|pair| pair[1].parse()) {
+        set_color(choice);
+    }
+    if args.iter().any(|arg| arg == "--no-color") {
+        set_color(ColorChoice::Never);
+    }
+
+    let args = Args::from_args(args.into_iter(), description)?;
+    ColorChoice::from_flags(&args.flags)?;
+    Ok(args)
+}
+
+/// Display help or version information and exit, if that's what the error is.
+fn display_help(error: SomeError) -> SomeError {
+    match error.0.downcast::<NoInput>() {
+        Ok(no_input) => SomeError::new(no_input.display_help()),
+        Err(error) => SomeError(error),
+    }
+}
+
+/// Run the app with the parsed arguments,
+/// returning any error along with the format it was requested to be displayed in.
+fn try_run(
+    description: Description,
+    args: impl Iterator<Item = String>,
+    main: impl FnOnce(Args) -> Result<Outcome, SomeError>,
+) -> Result<Outcome, (SomeError, ErrorFormat)> {
+    // errors in the arguments are displayed in the requested format too, so find it up front;
+    // an invalid format is reported in the default one once parsing finishes
+    let args: Vec<_> = args.collect();
+    let format = args
+        .windows(2)
+        .rev()
+        .find(|pair| pair[0] == "--error-format");
+    let mut format = format
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or_default();
+
+    parse_args(description, args.into_iter())
+        .and_then(|args| {
+            format = args.flags.parse(ERROR_FORMAT.name)?.unwrap_or_default();
+            QUIET.store(args.flags.is_set(QUIET_FLAG.name), Ordering::Relaxed);
+            main(args)
+        })
+        .map_err(|error| (error, format))
+}
+
+/// Run the app with the command line arguments,
+/// then display any error in the requested format and exit with its [`SomeError::exit_code`],
+/// or otherwise with the code of the outcome.
+fn run(description: Description, main: impl FnOnce(Args) -> Result<Outcome, SomeError>) {
+    let code = report(try_run(description, env::args(), main));
+    if code != 0 {
+        process::exit(code.into());
+    }
+}
+
+/// Display the error, if any, in the requested format, then return the code to exit with.
+fn report(result: Result<Outcome, (SomeError, ErrorFormat)>) -> u8 {
+    let (error, format) = match result {
+        Ok(outcome) => return outcome.code(),
+        Err(error) => error,
+    };
+
+    let error = display_help(error);
+    if !error.is_broken_pipe() {
+        display_error(&error, format);
+    }
+    error.exit_code()
+}
+
+/// Display the error on stderr in the format, colored if enabled.
+fn display_error(error: &SomeError, format: ErrorFormat) {
+    let error = format.render(error);
+    if format == ErrorFormat::Text && color().enabled(&io::stderr()) {
+        eprintln!("{}", color::paint_error(&error));
+    } else {
+        eprintln!("{error}");
+    }
+}
+
+/// Run the function, measuring how long it takes only if requested.
+fn timed<T>(time: bool, main: impl FnOnce() -> T) -> (T, Option<Duration>) {
+    if time {
+        let start = Instant::now();
+        let output = main();
+        (output, Some(start.elapsed()))
+    } else {
+        (main(), None)
+    }
+}
+
+/// Print the durations of all runs to stderr; does nothing if there are none.
+fn report_durations(durations: &[Duration]) {
+    match durations {
+        [] => {}
+        [duration] => eprintln!("time: {duration:?}"),
+        durations => {
+            let min = durations.iter().min().copied().unwrap_or_default();
+            let max = durations.iter().max().copied().unwrap_or_default();
+            let total: Duration = durations.iter().sum();
+            let runs = durations.len();
+            let mean = total.div_f64(runs as f64);
+
+            eprintln!("time: min {min:?}, mean {mean:?}, max {max:?} over {runs} runs");
+        }
+    }
+}
+
+/// Print the size of the input, and how long it took to read and solve, to stderr.
+fn report_profile(size: InputSize, read: Option<Duration>, solve: &[Duration]) {
+    let InputSize { bytes, lines } = size;
+    let read = read.unwrap_or_default();
+    let runs = solve.len();
+    let solve: Duration = solve.iter().sum();
+
+    eprint!("profile: read {bytes} bytes, {lines} lines in {read:?}; solved in {solve:?}");
+    if runs > 1 {
+        eprint!(" over {runs} runs");
+    }
+    eprintln!();
+}
+
+/// The size of input, as reported with `--profile`.
+#[derive(Debug, Clone, Copy, Default)]
+struct InputSize {
+    bytes: usize,
+    lines: usize,
+}
+
+impl Add for InputSize {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            bytes: self.bytes + other.bytes,
+            lines: self.lines + other.lines,
+        }
+    }
+}
+
+/// Input that can be divided into segments for `--split`.
+trait Segments: Sized {
+    fn segments(self, delimiter: &str) -> Vec<Self>;
+}
+
+impl Segments for String {
+    fn segments(self, delimiter: &str) -> Vec<Self> {
+        let segments = split_segments(&self, delimiter);
+        segments.into_iter().map(str::to_owned).collect()
+    }
+}
+
+impl Segments for Vec<InputFile> {
+    fn segments(self, delimiter: &str) -> Vec<Self> {
+        self.into_iter()
+            .flat_map(|file| {
+                let InputFile { input, contents } = file;
+                contents
+                    .segments(delimiter)
+                    .into_iter()
+                    .map(move |contents| {
+                        vec![InputFile {
+                            input: input.clone(),
+                            contents,
+                        }]
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Input that can be measured for `--profile`.
+trait Size {
+    fn size(&self) -> InputSize;
+}
+
+impl Size for String {
+    fn size(&self) -> InputSize {
+        InputSize {
+            bytes: self.len(),
+            lines: self.bytes().filter(|&byte| byte == b'\n').count(),
+        }
+    }
+}
+
+impl Size for Vec<InputFile> {
+    fn size(&self) -> InputSize {
+        self.iter()
+            .map(|file| file.contents.size())
+            .fold(InputSize::default(), Add::add)
+    }
+}
+
+/// Provides input for advent of code to the provided function, catching any panics.
+///
+/// Like [`with`], but if the provided function panics,
+/// the panic message is displayed like any other error instead of the standard panic output,
+/// and the app exits.
+/// Prefer [`with`] while debugging, as the standard output includes the panic location.
+pub fn with_catch_panic<T: IntoOutcome>(
+    description: Description,
+    main: impl FnOnce(String) -> Result<T, SomeError>,
+) {
+    with(description, |input| catch_panic(|| main(input)));
+}
+
+fn catch_panic<T>(main: impl FnOnce() -> Result<T, SomeError>) -> Result<T, SomeError> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(main));
+    panic::set_hook(hook);
+
+    result.unwrap_or_else(|payload| Err(SomeError::new(Panic::from_payload(&*payload))))
+}
+
+/// Returns a [`String`] containing input.
+///
+/// Returns a [`String`] with the input collected from standard input or a file,
+/// as specified with command line arguments.
+///
+/// # Errors
+///
+/// An error is returned if no arguments are passed,
+/// or if an error is encountered while reading input from stdin or a file.
+/// The input can't be split into segments to solve separately, so `--split` is an error too.
+pub fn get(description: Description) -> Result<String, SomeError> {
+    get_with_args(description, env::args()).map_err(display_help)
+}
+
+/// Returns a [`String`] containing input, using the given arguments.
+///
+/// Like [`get`], but the arguments are taken from `args` instead of [`env::args`],
+/// with the binary name first.
+/// Help and version information aren't displayed; they're returned as a [`NoInput`] error.
+///
+/// # Errors
+///
+/// An error is returned if the arguments are invalid,
+/// or if an error is encountered while reading input from stdin or a file.
+/// ```
+/// use input::{Description, NoInput};
+///
+/// let description = Description {
+///     name: "app",
+///     bin_name: "app".into(),
+///     description: "An app",
+///     version: (1, 0, 0),
+///     flags: &[],
+///     standalone_flags: &[],
+///     env_vars: &[],
+///     examples: &[],
+/// };
+/// let args = ["app", "--version"].map(String::from).into_iter();
+///
+/// let error = input::get_with_args(description, args).unwrap_err();
+/// assert!(matches!(error.0.downcast_ref(), Some(NoInput::Version(_))));
+/// ```
+///
+/// Builtin flags apply just as they would on the command line,
+/// so a header can be skipped, here after its line ending is normalized:
+/// ```
+/// # use input::Description;
+/// # use std::{env, fs};
+/// # let description = Description {
+/// #     name: "app", bin_name: "app".into(), description: "", version: (0, 0, 0),
+/// #     flags: &[], standalone_flags: &[], env_vars: &[], examples: &[],
+/// # };
+/// let file = env::temp_dir().join("input-skip-header.txt");
+/// fs::write(&file, "# generated\r\nA Y\r\nB X\r\n").unwrap();
+///
+/// let file = file.to_string_lossy().into_owned();
+/// let args = ["app", "--skip-header", "--normalize-newlines", &file];
+/// let input = input::get_with_args(description, args.map(String::from).into_iter());
+/// assert_eq!(input.unwrap(), "A Y\nB X\n");
+/// ```
+///
+/// With `--fail-on-empty`, input that's empty or only whitespace is an error:
+/// ```
+/// # use input::Description;
+/// # use std::{env, fs};
+/// # let description = || Description {
+/// #     name: "app", bin_name: "app".into(), description: "", version: (0, 0, 0),
+/// #     flags: &[], standalone_flags: &[], env_vars: &[], examples: &[],
+/// # };
+/// let file = env::temp_dir().join("input-fail-on-empty.txt");
+/// fs::write(&file, " \n\n").unwrap();
+///
+/// let file = file.to_string_lossy().into_owned();
+/// let args = ["app", "--fail-on-empty", &file].map(String::from);
+/// let error = input::get_with_args(description(), args.into_iter()).unwrap_err();
+/// assert_eq!(error.to_string(), "input is empty");
+///
+/// let args = ["app", &file].map(String::from);
+/// let input = input::get_with_args(description(), args.into_iter());
+/// assert_eq!(input.unwrap(), " \n\n");
+/// ```
+pub fn get_with_args(
+    description: Description,
+    args: impl Iterator<Item = String>,
+) -> Result<String, SomeError> {
+    let Args { input, flags, .. } = parse_args(description, args)?;
+    if flags.is_set(SPLIT.name) {
+        return Err(SomeError::new(UNSUPPORTED_SPLIT));
+    }
+
+    read(input, &flags)
+}
+
+/// Read input as configured by the builtin flags.
+fn read(input: Input, flags: &Flags) -> Result<String, SomeError> {
+    prompt(&input, flags);
+    let input = if flags.is_set(PROGRESS.name) {
+        read_with_progress_bar(input)?
+    } else {
+        input.read_with(ReadOptions::from_flags(flags)?)?
+    };
+
+    let input = prepare(input, flags)?;
+    if flags.is_set(WC.name) {
+        eprintln!("wc: {}", stats(&input));
+    }
+    if flags.is_set(TEE.name) {
+        tee("tee: ", &input, flags.parse(TEE_LINES.name)?);
+    }
+    if let Some(file) = flags.value(RECORD.name) {
+        record(file, &input)?;
+    }
+    if flags.is_set(FAIL_ON_EMPTY.name) && input.trim().is_empty() {
+        return Err(SomeError::new(EMPTY_INPUT));
+    }
+    Ok(input)
+}
+
+/// Read input, drawing a bar on stderr with the percentage read so far,
+/// or just the number of bytes read if the size of the input isn't known.
+fn read_with_progress_bar(input: Input) -> Result<String, IoError> {
+    const WIDTH: u64 = 30;
+
+    let mut last = None;
+    let input = input.read_to_string_progress(|read, total| {
+        let progress = match total {
+            Some(total) => {
+                let percent = (read * 100).checked_div(total).unwrap_or(100).min(100);
+                if last == Some(percent) {
+                    return;
+                }
+                last = Some(percent);
+
+                let filled = (percent * WIDTH / 100) as usize;
+                let bar = format!(
+                    "{}{}",
+                    "#".repeat(filled),
+                    " ".repeat(WIDTH as usize - filled)
+                );
+                format!("[{bar}] {percent:>3}%")
+            }
+            None => format!("{read} bytes"),
+        };
+        eprint!("\rreading input {progress}");
+    });
+    eprintln!();
+
+    input
+}
+
+/// Read each file of input separately, as configured by the builtin flags.
+fn read_each(input: Input, flags: &Flags) -> Result<Vec<InputFile>, SomeError> {
+    if flags.is_set(PROGRESS.name) {
+        return Err(SomeError::new(Message(
+            "'--progress' isn't supported by this app, as it reads each file separately",
+        )));
+    }
+
+    prompt(&input, flags);
+    let mut files = input.read_each_with(ReadOptions::from_flags(flags)?)?;
+    for file in &mut files {
+        file.contents = prepare(mem::take(&mut file.contents), flags)?;
+        if flags.is_set(WC.name) {
+            eprintln!("wc: {}: {}", file.name(), stats(&file.contents));
+        }
+        if flags.is_set(TEE.name) {
+            let prefix = format!("tee: {}: ", file.name());
+            tee(&prefix, &file.contents, flags.parse(TEE_LINES.name)?);
+        }
+    }
+    if let Some(file) = flags.value(RECORD.name) {
+        let contents: String = files.iter().map(|file| file.contents.as_str()).collect();
+        record(file, &contents)?;
+    }
+    let empty = files.iter().all(|file| file.contents.trim().is_empty());
+    if flags.is_set(FAIL_ON_EMPTY.name) && empty {
+        return Err(SomeError::new(EMPTY_INPUT));
+    }
+    Ok(files)
+}
+
+/// Echo the lines of input to stderr after the prefix, as requested with `--tee`,
+/// stopping after `max_lines` if given, with a count of the lines left out.
+fn tee(prefix: &str, input: &str, max_lines: Option<usize>) {
+    let mut stderr = io::stderr().lock();
+    let mut lines = input.lines();
+    for line in lines.by_ref().take(max_lines.unwrap_or(usize::MAX)) {
+        let _ = writeln!(stderr, "{prefix}{line}");
+    }
+
+    let omitted = lines.count();
+    if omitted > 0 {
+        let lines = if omitted == 1 { "line" } else { "lines" };
+        let _ = writeln!(stderr, "{prefix}... {omitted} more {lines}");
+    }
+}
+
+/// Write input to the file, as requested with `--record`,
+/// for the same run to be reproduced by passing the file as input.
+///
+/// The input is recorded as passed to the solution, after the builtin flags shaped it,
+/// so it's replayed without flags like `--head` or `--decompress`;
+/// files read separately are recorded one after another, as if concatenated.
+fn record(file: &str, input: &str) -> Result<(), SomeError> {
+    fs::write(path::long(file), input).map_err(|error| {
+        let file = path::display(file);
+        SomeError::new(error).context(format!("can't record input to '{file}'"))
+    })
+}
+
+/// Split input on lines equal to `delimiter`, as requested with `--split`.
+///
+/// The delimiting lines are left out, and each segment keeps the line endings of its lines.
+/// A line ending in `\r\n` matches too, and input without any delimiting lines is one segment.
+/// ```
+/// assert_eq!(input::split_segments("A Y\n---\nB X\nC Z\n", "---"), ["A Y\n", "B X\nC Z\n"]);
+/// assert_eq!(input::split_segments("1\r\n--\r\n2", "--"), ["1\r\n", "2"]);
+/// assert_eq!(input::split_segments("A Y\n", "---"), ["A Y\n"]);
+/// assert_eq!(input::split_segments("", "---"), [""]);
+/// ```
+pub fn split_segments<'a>(input: &'a str, delimiter: &str) -> Vec<&'a str> {
+    let mut segments = Vec::new();
+    let (mut start, mut end) = (0, 0);
+    for line in input.split_inclusive('\n') {
+        let text = line.strip_suffix('\n').unwrap_or(line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        if text == delimiter {
+            segments.push(&input[start..end]);
+            start = end + line.len();
+        }
+        end += line.len();
+    }
+    segments.push(&input[start..]);
+
+    segments
+}
+
+/// Convert `\r\n` and lone `\r` line endings to `\n`, borrowing the input if there are none.
+/// ```
+/// use input::normalize_newlines;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(normalize_newlines("a\r\nb\rc\nd\r\r\n"), "a\nb\nc\nd\n\n");
+/// assert!(matches!(normalize_newlines("a\nb\n"), Cow::Borrowed("a\nb\n")));
+/// ```
+pub fn normalize_newlines(input: &str) -> Cow<'_, str> {
+    if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Count the bytes, lines, and words of the input, and the length of its longest line.
+///
+/// Lines are counted as with [`str::lines`], so a final line ending doesn't start another line,
+/// and line lengths are in bytes, without the line ending.
+/// Words are separated by whitespace, as with [`str::split_whitespace`].
+/// ```
+/// use input::InputStats;
+///
+/// let stats = input::stats("1000\n2000 3000\n\n4000");
+/// assert_eq!(
+///     stats,
+///     InputStats { bytes: 20, lines: 4, words: 4, max_line_len: 9 }
+/// );
+///
+/// assert_eq!(input::stats("a b\n").lines, 1);
+/// assert_eq!(input::stats(""), InputStats::default());
+/// ```
+pub fn stats(input: &str) -> InputStats {
+    let mut stats = InputStats {
+        bytes: input.len(),
+        ..InputStats::default()
+    };
+    for line in input.lines() {
+        stats.lines += 1;
+        stats.words += line.split_whitespace().count();
+        stats.max_line_len = stats.max_line_len.max(line.len());
+    }
+
+    stats
+}
+
+/// Counts describing the shape of input, as returned by [`stats`] and printed with `--wc`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputStats {
+    pub bytes: usize,
+    pub lines: usize,
+    pub words: usize,
+    /// The length in bytes of the longest line, without its line ending.
+    pub max_line_len: usize,
+}
+
+impl Display for InputStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self {
+            bytes,
+            lines,
+            words,
+            max_line_len,
+        } = self;
+        write!(
+            f,
+            "{lines} lines, {words} words, {bytes} bytes; the longest line is {max_line_len} bytes"
+        )
+    }
+}
+
+/// Normalize newlines, then skip a header and select lines of the input,
+/// if requested with the builtin flags.
+fn prepare(mut input: String, flags: &Flags) -> Result<String, SomeError> {
+    if flags.is_set(NORMALIZE_NEWLINES.name) {
+        if let Cow::Owned(normalized) = normalize_newlines(&input) {
+            input = normalized;
+        }
+    }
+
+    let header = match flags.parse(HEADER_LINES.name)? {
+        Some(lines) => lines,
+        None => usize::from(flags.is_set(SKIP_HEADER.name)),
+    };
+    if header > 0 {
+        let start = input.len() - tail_after(&input, header).len();
+        input.drain(..start);
+    }
+
+    if let Some(lines) = flags.parse(HEAD.name)? {
+        input.truncate(head(&input, lines).len());
+    }
+    if let Some(lines) = flags.parse(TAIL.name)? {
+        let start = input.len() - tail(&input, lines).len();
+        input.drain(..start);
+    }
+
+    Ok(input)
+}
+
+/// Returns the first lines of the input, each with its newline if it had one.
+fn head(input: &str, lines: usize) -> &str {
+    let len = input.split_inclusive('\n').take(lines).map(str::len).sum();
+    &input[..len]
+}
+
+/// Returns the input after the first lines.
+fn tail_after(input: &str, lines: usize) -> &str {
+    &input[head(input, lines).len()..]
+}
+
+/// Returns the last lines of the input, each with its newline if it had one.
+fn tail(input: &str, lines: usize) -> &str {
+    let skipped = input.split_inclusive('\n').count().saturating_sub(lines);
+    let start = input
+        .split_inclusive('\n')
+        .take(skipped)
+        .map(str::len)
+        .sum();
+    &input[start..]
+}
+
+/// Prompt for input if requested with `--interactive` and stdin is a terminal.
+fn prompt(input: &Input, flags: &Flags) {
+    if input.is_stdin() && flags.is_set(INTERACTIVE.name) && io::stdin().is_terminal() {
+        eprintln!("Enter input, end with Ctrl-D:");
+    }
+}
+
+/// Creates a [`Description`] from the manifest of the crate invoking this macro.
+///
+/// The name, binary name, and version are taken from the `CARGO_PKG_NAME`,
+/// `CARGO_BIN_NAME`, and `CARGO_PKG_VERSION` environment variables set by cargo,
+/// so help information always matches `Cargo.toml`.
+/// The version must start with `major.minor.patch`, otherwise compilation fails.
+/// Other fields can be set with struct update syntax.
+/// ```ignore
+/// use input::{Description, Flag};
+///
+/// let description = Description {
+///     flags: &[Flag {
+///         name: "verbose",
+///         value: None,
+///         help: "Print more information",
+///     }],
+///     ..input::description!("<description>")
+/// };
+/// ```
+#[macro_export]
+macro_rules! description {
+    ($description:expr) => {
+        $crate::Description {
+            name: ::core::env!("CARGO_PKG_NAME"),
+            bin_name: ::std::borrow::Cow::Borrowed(::core::env!("CARGO_BIN_NAME")),
+            description: $description,
+            version: const { $crate::Description::cargo_version(::core::env!("CARGO_PKG_VERSION")) },
+            flags: &[],
+            standalone_flags: &[],
+            env_vars: &[],
+            examples: &[],
+        }
+    };
+}
+
+/// Metadata of the app to be used when displaying help information.
+#[derive(Debug, Clone)]
+pub struct Description {
+    pub name: &'static str,
+    /// The name of the binary executing this code.
+    pub bin_name: Cow<'static, str>,
+    pub description: &'static str,
+    pub version: (u16, u16, u16),
+    /// Command line flags understood by the app itself, in addition to the builtin ones.
+    pub flags: &'static [Flag],
+    /// App specific flags that do the app's work without any input, like generating some.
+    ///
+    /// With one of these flags, no input needs to be given, and none is read.
+    pub standalone_flags: &'static [Flag],
+    /// Names of environment variables the app honors, paired with a short description.
+    pub env_vars: &'static [(&'static str, &'static str)],
+    /// Command lines showing how to run the app, listed at the end of the help text.
+    pub examples: &'static [&'static str],
+}
+
+impl Description {
+    /// Returns the version formatted as `major.minor.patch`.
+    pub fn version_string(&self) -> String {
+        let (major, minor, patch) = self.version;
+        format!("{major}.{minor}.{patch}")
+    }
+
+    /// Replace the version with one parsed from a `major.minor.patch` string.
+    ///
+    /// ```
+    /// # use input::Description;
+    /// let description = Description {
+    ///     name: "<name>",
+    ///     bin_name: "<binary-name>".into(),
+    ///     description: "<description>",
+    ///     version: (0, 0, 0),
+    ///     flags: &[],
+    ///     standalone_flags: &[],
+    ///     env_vars: &[],
+    ///     examples: &[],
+    /// };
+    ///
+    /// let description = description.with_version_str("1.2.3").unwrap();
+    /// assert_eq!(description.version, (1, 2, 3));
+    /// assert_eq!(description.version_string(), "1.2.3");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the string isn't exactly three dot separated integers,
+    /// then [`VersionError`] is returned.
+    pub fn with_version_str(mut self, version: &str) -> Result<Self, VersionError> {
+        self.version = parse_version(version)?;
+        Ok(self)
+    }
+
+    /// Parse the `major.minor.patch` prefix of a cargo package version at compile time.
+    ///
+    /// Any pre-release or build metadata following the patch number is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the version doesn't start with three dot separated integers.
+    #[doc(hidden)]
+    pub const fn cargo_version(version: &str) -> (u16, u16, u16) {
+        let bytes = version.as_bytes();
+        let mut parts = [0_u16; 3];
+        let mut part = 0;
+        let mut digits = 0;
+        let mut i = 0;
+
+        while i < bytes.len() && !matches!(bytes[i], b'-' | b'+') {
+            match bytes[i] {
+                digit @ b'0'..=b'9' => {
+                    let Some(value) = parts[part].checked_mul(10) else {
+                        panic!("version number too large");
+                    };
+                    let Some(value) = value.checked_add((digit - b'0') as u16) else {
+                        panic!("version number too large");
+                    };
+                    parts[part] = value;
+                    digits += 1;
+                }
+                b'.' if digits > 0 && part < 2 => {
+                    part += 1;
+                    digits = 0;
+                }
+                _ => panic!("expected version of the form 'major.minor.patch'"),
+            }
+            i += 1;
+        }
+
+        assert!(
+            part == 2 && digits > 0,
+            "expected version of the form 'major.minor.patch'"
+        );
+
+        (parts[0], parts[1], parts[2])
+    }
+
+    /// Check that the metadata can produce sensible help information.
+    ///
+    /// ```
+    /// # use input::{Description, DescriptionError};
+    /// let description = Description {
+    ///     name: "app",
+    ///     bin_name: "".into(),
+    ///     description: "Solves a puzzle.\nIn two parts.",
+    ///     version: (1, 2, 3),
+    ///     flags: &[],
+    ///     standalone_flags: &[],
+    ///     env_vars: &[],
+    ///     examples: &[],
+    /// };
+    ///
+    /// assert_eq!(description.to_string(), "app v1.2.3 — Solves a puzzle.");
+    /// assert!(matches!(description.validate(), Err(DescriptionError::EmptyBinName)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If [`Self::name`] or [`Self::bin_name`] is empty, then [`DescriptionError`] is returned.
+    pub fn validate(&self) -> Result<(), DescriptionError> {
+        if self.name.is_empty() {
+            Err(DescriptionError::EmptyName)
+        } else if self.bin_name.is_empty() {
+            Err(DescriptionError::EmptyBinName)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the first line of the description that isn't empty.
+    fn summary(&self) -> Option<&'static str> {
+        self.description
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+    }
+
+    /// Find a builtin or app specific flag by its long form; builtin flags take precedence.
+    fn flag(&self, arg: &str) -> Option<&'static Flag> {
+        let name = arg.strip_prefix("--")?;
+        BUILTIN_FLAGS
+            .iter()
+            .chain(HIDDEN_FLAGS)
+            .chain(self.flags)
+            .chain(self.standalone_flags)
+            .find(|flag| flag.name == name)
+    }
+
+    /// Find the long option closest to a mistyped one, if it's within a couple of edits.
+    fn suggestion(&self, arg: &str) -> Option<&'static str> {
+        const MAX_EDITS: usize = 2;
+
+        let name = arg.strip_prefix("--")?;
+        let flags = BUILTIN_FLAGS
+            .iter()
+            .chain(self.flags)
+            .chain(self.standalone_flags)
+            .map(|flag| flag.name);
+        ["help", "version", "version-json", "stdin"]
+            .into_iter()
+            .chain(flags)
+            .map(|option| (edit_distance(name, option), option))
+            .filter(|&(edits, _)| edits <= MAX_EDITS && edits < name.len())
+            .min_by_key(|&(edits, _)| edits)
+            .map(|(_, option)| option)
+    }
+}
+
+/// The number of single character insertions, deletions, or substitutions
+/// to turn `a` into `b`, known as the levenshtein distance.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // the distances from a prefix of `a` to each prefix of `b`, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Displays a one line summary, with just the first line of the description.
+impl Display for Description {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = self.name;
+        let version = self.version_string();
+
+        match self.summary() {
+            Some(description) => write!(f, "{name} v{version} — {description}"),
+            None => write!(f, "{name} v{version}"),
+        }
+    }
+}
+
+/// An error returned when a [`Description`] would produce broken help information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptionError {
+    EmptyName,
+    EmptyBinName,
+}
+
+impl Error for DescriptionError {}
+
+impl Display for DescriptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let field = match self {
+            Self::EmptyName => "name",
+            Self::EmptyBinName => "bin_name",
+        };
+        write!(f, "invalid app description; '{field}' is empty")
+    }
+}
+
+fn parse_version(version: &str) -> Result<(u16, u16, u16), VersionError> {
+    let error = |source| VersionError {
+        version: version.to_owned(),
+        source,
+    };
+
+    let mut parts = version.split('.').map(str::parse::<u16>);
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(major), Some(minor), Some(patch), None) => {
+            let part = |part: Result<u16, _>| part.map_err(|parse| error(Some(parse)));
+            Ok((part(major)?, part(minor)?, part(patch)?))
+        }
+        _ => Err(error(None)),
+    }
+}
+
+/// An error returned when a version string isn't of the form `major.minor.patch`.
+#[derive(Debug, Clone)]
+pub struct VersionError {
+    pub version: String,
+    pub source: Option<ParseIntError>,
+}
+
+impl Error for VersionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|error| error as _)
+    }
+}
+
+impl Display for VersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let version = &self.version;
+        write!(
+            f,
+            "invalid version '{version}'; expected 'major.minor.patch'"
+        )
+    }
+}
+
+/// A command line flag specific to an app, rendered in help information.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    /// The long name of the flag, without the leading `--`.
+    pub name: &'static str,
+    /// The name of the value the flag takes, or [`None`] if it takes no value.
+    pub value: Option<&'static str>,
+    /// A short description of the flag.
+    pub help: &'static str,
+}
+
+impl Display for Flag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(value) => write!(f, "--{} <{value}>", self.name),
+            None => write!(f, "--{}", self.name),
+        }
+    }
+}
+
+const INPUT_GLOB: Flag = Flag {
+    name: "input-glob",
+    value: Some("PATTERN"),
+    help: "Read every file matching the shell style PATTERN, instead of FILE",
+};
+
+const INPUT_OR_STDIN: Flag = Flag {
+    name: "input-or-stdin",
+    value: Some("FILE"),
+    help: "Read FILE if it exists, otherwise read stdin",
+};
+
+const FD: Flag = Flag {
+    name: "fd",
+    value: Some("N"),
+    help: "Read input from the inherited file descriptor N, instead of FILE; Unix only",
+};
+
+const CLIPBOARD: Flag = Flag {
+    name: "clipboard",
+    value: None,
+    help: "Read input from the clipboard, instead of FILE; needs the 'clipboard' feature",
+};
+
+const DAY: Flag = Flag {
+    name: "day",
+    value: Some("D"),
+    help: "Fetch the input of day D from adventofcode.com using the session cookie in \
+        AOC_SESSION, instead of FILE; input is cached after the first fetch",
+};
+
+const YEAR: Flag = Flag {
+    name: "year",
+    value: Some("YEAR"),
+    help: "Fetch input with '--day' from the puzzles of YEAR, 2022 by default",
+};
+
+const CANONICALIZE: Flag = Flag {
+    name: "canonicalize",
+    value: None,
+    help: "Resolve FILE to an absolute path without symlinks, to name it in errors",
+};
+
+const INTERACTIVE: Flag = Flag {
+    name: "interactive",
+    value: None,
+    help: "Without FILE, prompt for input to be typed or pasted into the terminal",
+};
+
+const MAX_BYTES: Flag = Flag {
+    name: "max-bytes",
+    value: Some("N"),
+    help: "Fail instead of reading more than N bytes of input",
+};
+
+const PROGRESS: Flag = Flag {
+    name: "progress",
+    value: None,
+    help: "Draw a bar on stderr showing how much of the input has been read",
+};
+
+const UNTIL: Flag = Flag {
+    name: "until",
+    value: Some("MARKER"),
+    help: "Stop reading stdin at the first line equal to MARKER",
+};
+
+const LOSSY: Flag = Flag {
+    name: "lossy",
+    value: None,
+    help: "Replace invalid utf-8 in the input instead of failing",
+};
+
+const NORMALIZE_NEWLINES: Flag = Flag {
+    name: "normalize-newlines",
+    value: None,
+    help: "Convert '\\r\\n' and lone '\\r' line endings in the input to '\\n'",
+};
+
+const SKIP_HEADER: Flag = Flag {
+    name: "skip-header",
+    value: None,
+    help: "Skip the first line of input; see '--header-lines' to skip more",
+};
+
+// a count after '--skip-header' couldn't be told apart from FILE, so it takes its own flag
+const HEADER_LINES: Flag = Flag {
+    name: "header-lines",
+    value: Some("N"),
+    help: "Skip the first N lines of input",
+};
+
+const HEAD: Flag = Flag {
+    name: "head",
+    value: Some("N"),
+    help: "Solve with only the first N lines of input",
+};
+
+const TAIL: Flag = Flag {
+    name: "tail",
+    value: Some("N"),
+    help: "Solve with only the last N lines of input",
+};
+
+const DECOMPRESS: Flag = Flag {
+    name: "decompress",
+    value: Some("FORMAT"),
+    help: "Decompress input from FORMAT, one of 'gzip', 'zstd', 'bzip2', 'auto' to detect it, or 'none'",
+};
+
+const CHECK: Flag = Flag {
+    name: "check",
+    value: None,
+    help: "Only check that the input is valid, without solving",
+};
+
+const UNSUPPORTED_CHECK: Message = Message("'--check' isn't supported by this app");
+
+const FAIL_ON_EMPTY: Flag = Flag {
+    name: "fail-on-empty",
+    value: None,
+    help: "Fail if input is empty or only whitespace, instead of solving it",
+};
+
+const EMPTY_INPUT: Message = Message("input is empty");
+
+const TIME: Flag = Flag {
+    name: "time",
+    value: None,
+    help: "Print how long solving took to stderr",
+};
+
+const REPEAT: Flag = Flag {
+    name: "repeat",
+    value: Some("N"),
+    help: "Solve N times on the same input, then print the shortest, mean, and longest time",
+};
+
+const WATCH: Flag = Flag {
+    name: "watch",
+    value: None,
+    help: "Solve again whenever the input file changes, until stopped with Ctrl-C",
+};
+
+const COLOR: Flag = Flag {
+    name: "color",
+    value: Some("WHEN"),
+    help: "Color help and errors 'always', 'never', or on a terminal with 'auto'",
+};
+
+const NO_COLOR: Flag = Flag {
+    name: "no-color",
+    value: None,
+    help: "Don't color help and errors, the same as '--color never'; \
+        a non-empty NO_COLOR does the same for '--color auto'",
+};
+
+const ERROR_FORMAT: Flag = Flag {
+    name: "error-format",
+    value: Some("FORMAT"),
+    help: "Print errors as FORMAT, one of 'text' or 'json'",
+};
+
+const NO_TRAILING_NEWLINE: Flag = Flag {
+    name: "no-trailing-newline",
+    value: None,
+    help: "Don't print a newline after the last line of output",
+};
+
+const SPLIT: Flag = Flag {
+    name: "split",
+    value: Some("DELIMITER"),
+    help: "Solve each segment of input between lines equal to DELIMITER separately",
+};
+
+const UNSUPPORTED_SPLIT: Message =
+    Message("'--split' isn't supported by this app, as it solves all of its input at once");
+
+const WC: Flag = Flag {
+    name: "wc",
+    value: None,
+    help: "Print the number of lines, words, and bytes of input to stderr, then solve",
+};
+
+const TEE: Flag = Flag {
+    name: "tee",
+    value: None,
+    help: "Echo input to stderr as it's passed to the solution, each line after 'tee: '",
+};
+
+const TEE_LINES: Flag = Flag {
+    name: "tee-lines",
+    value: Some("N"),
+    help: "Only echo the first N lines of input with '--tee'",
+};
+
+const RECORD: Flag = Flag {
+    name: "record",
+    value: Some("FILE"),
+    help: "Write input as passed to the solution to FILE, to replay it by passing FILE as input",
+};
+
+const PROFILE: Flag = Flag {
+    name: "profile",
+    value: None,
+    help: "Print the size of the input, and how long reading and solving took, to stderr",
+};
+
+const PART: Flag = Flag {
+    name: "part",
+    value: Some("PART"),
+    help: "Print just the answer to PART of the puzzle, either '1' or '2'",
+};
+
+const UNSUPPORTED_PART: Message = Message("'--part' isn't supported by this app");
+
+const PREFIX: Flag = Flag {
+    name: "prefix",
+    value: None,
+    help: "Label each answer with its part, as in 'Part 1: ANSWER'",
+};
+
+const UNSUPPORTED_PREFIX: Message = Message("'--prefix' isn't supported by this app");
+
+const GROUP_DIGITS: Flag = Flag {
+    name: "group-digits",
+    value: None,
+    help: "Print answers that are whole numbers with commas between thousands, as in '1,000'",
+};
+
+const UNSUPPORTED_GROUP_DIGITS: Message = Message("'--group-digits' isn't supported by this app");
+
+const QUIET_FLAG: Flag = Flag {
+    name: "quiet",
+    value: None,
+    help: "Don't print notes to stderr, only errors and requested measurements",
+};
+
+const DUMP_ARGS: Flag = Flag {
+    name: "dump-args",
+    value: None,
+    help: "Print how the arguments were parsed to stderr, then exit",
+};
+
+/// Flags handled by this crate that are left out of the help text, for debugging.
+const HIDDEN_FLAGS: &[Flag] = &[DUMP_ARGS];
+
+/// Flags handled by this crate that have no short form.
+const BUILTIN_FLAGS: &[Flag] = &[
+    INPUT_GLOB,
+    INPUT_OR_STDIN,
+    FD,
+    CLIPBOARD,
+    DAY,
+    YEAR,
+    INTERACTIVE,
+    CANONICALIZE,
+    UNTIL,
+    MAX_BYTES,
+    PROGRESS,
+    LOSSY,
+    NORMALIZE_NEWLINES,
+    SKIP_HEADER,
+    HEADER_LINES,
+    HEAD,
+    TAIL,
+    DECOMPRESS,
+    CHECK,
+    FAIL_ON_EMPTY,
+    TIME,
+    REPEAT,
+    WATCH,
+    WC,
+    TEE,
+    TEE_LINES,
+    RECORD,
+    PROFILE,
+    ERROR_FORMAT,
+    COLOR,
+    NO_COLOR,
+    NO_TRAILING_NEWLINE,
+    SPLIT,
+    PART,
+    PREFIX,
+    GROUP_DIGITS,
+    QUIET_FLAG,
+];
+
+/// Builtin and app specific flags found on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct Flags(Vec<(&'static str, Option<String>)>);
+
+impl Flags {
+    /// Returns `true` if the named flag was passed at least once.
+    pub fn is_set(&self, name: &str) -> bool {
+        self.0.iter().any(|&(flag, _)| flag == name)
+    }
+
+    /// Returns the value of the named flag; the last occurrence wins.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .rev()
+            .find(|&&(flag, _)| flag == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Parse the value of the named flag; the last occurrence wins.
+    ///
+    /// # Errors
+    ///
+    /// If the value can't be parsed, then [`FlagError`] is returned.
+    pub fn parse<T>(&self, name: &'static str) -> Result<Option<T>, FlagError>
+    where
+        T: FromStr,
+        T::Err: Error + Send + Sync + 'static,
+    {
+        self.value(name)
+            .map(|value| {
+                value.parse().map_err(|error| FlagError {
+                    flag: name,
+                    value: value.to_owned(),
+                    error: Box::new(error),
+                })
+            })
+            .transpose()
+    }
+}
+
+/// An error returned when the value of a flag can't be parsed.
+#[derive(Debug)]
+pub struct FlagError {
+    pub flag: &'static str,
+    pub value: String,
+    pub error: Box<dyn Error + Send + Sync + 'static>,
+}
+
+impl Error for FlagError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+impl Display for FlagError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { flag, value, .. } = self;
+        write!(f, "invalid value '{value}' for '--{flag}'")
+    }
+}
+
+/// Command line arguments, parsed into an input source and app specific flags.
+#[derive(Debug, Clone)]
+pub struct Args {
+    pub input: Input,
+    pub flags: Flags,
+    /// One of [`Description::standalone_flags`] was found, so input shouldn't be read.
+    pub standalone: bool,
+}
+
+impl Args {
+    /// Parse arguments for input source and the flags declared in [`Description::flags`].
+    ///
+    /// # Errors
+    ///
+    /// If help information is requested, version information is requested,
+    /// an unknown argument is found, or no input source is given, then [`NoInput`] is returned.
+    ///
+    /// An argument of the form `@ARGS` is replaced by the whitespace separated arguments
+    /// read from the file `ARGS`, which can't reference argument files themselves.
+    /// To pass a file whose name starts with `@` as input, put it after `--`,
+    /// or escape it with another `@`, as in `@@file`.
+    /// ```
+    /// use input::{Args, Description};
+    /// use std::{env, fs};
+    ///
+    /// let path = env::temp_dir().join("input-doctest-args.txt");
+    /// fs::write(&path, "--stdin\n")?;
+    ///
+    /// let args = ["app".to_owned(), format!("@{}", path.display())];
+    /// let description = Description {
+    ///     name: "app",
+    ///     bin_name: "app".into(),
+    ///     description: "",
+    ///     version: (0, 0, 0),
+    ///     flags: &[],
+    ///     standalone_flags: &[],
+    ///     env_vars: &[],
+    ///     examples: &[],
+    /// };
+    /// let args = Args::from_args(args.into_iter(), description);
+    /// fs::remove_file(&path)?;
+    ///
+    /// assert!(args?.input.is_stdin());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_args(
+        args: impl Iterator<Item = String>,
+        mut description: Description,
+    ) -> Result<Self, NoInput> {
+        let mut args = ExpandedArgs::new(args);
+        if let Some(bin_name) = args.next() {
+            description.bin_name = Cow::from(bin_name);
+        }
+        let description = Box::new(description);
+
+        let mut input = None;
+        let mut flags = Flags::default();
+        let mut options_ended = false;
+
+        while let Some(arg) = args.next() {
+            let option = if options_ended { "" } else { arg.as_str() };
+
+            match option {
+                "--help" => return Err(NoInput::Help(description)),
+                "-h" => return Err(NoInput::ShortHelp(description)),
+                "--version" | "-V" => return Err(NoInput::Version(description)),
+                "--version-json" => return Err(NoInput::VersionJson(description)),
+                "--stdin" | "-0" if input.is_none() => input = Some(Input::Stdin),
+                "--" => options_ended = true,
+                _ if option.starts_with("@@") => {
+                    if input.is_some() {
+                        return Err(NoInput::Unexpected(arg, description));
+                    }
+                    input = Some(Input::from_path(arg[1..].to_owned()));
+                }
+                _ if option.len() > 1 && option.starts_with('@') => {
+                    if args.in_file() {
+                        return Err(NoInput::Invalid(
+                            "argument files can't reference other argument files",
+                            description,
+                        ));
+                    }
+
+                    let path = &option[1..];
+                    if let Err(error) = args.expand(path) {
+                        return Err(NoInput::ArgFile(path.to_owned(), error.kind(), description));
+                    }
+                }
+                _ if option.len() > 1 && option.starts_with('-') => {
+                    let Some(flag) = description.flag(option) else {
+                        return Err(NoInput::Unexpected(arg, description));
+                    };
+
+                    let value = match flag.value {
+                        Some(_) => match args.next() {
+                            Some(value) => Some(value),
+                            None => return Err(NoInput::MissingValue(flag, description)),
+                        },
+                        None => None,
+                    };
+
+                    flags.0.push((flag.name, value));
+                }
+                _ if input.is_none() => input = Some(Input::from_path(arg)),
+                _ => return Err(NoInput::Unexpected(arg, description)),
+            }
+        }
+
+        if let Some(pattern) = flags.value(INPUT_GLOB.name) {
+            if input.is_some() {
+                return Err(NoInput::Invalid(
+                    "'--input-glob' can't be used with a file or '--stdin'",
+                    description,
+                ));
+            }
+            input = Some(Input::Glob(pattern.to_owned()));
+        }
+
+        if let Some(file) = flags.value(INPUT_OR_STDIN.name) {
+            if input.is_some() {
+                return Err(NoInput::Invalid(
+                    "'--input-or-stdin' can't be used with a file, '--stdin', or '--input-glob'",
+                    description,
+                ));
+            }
+            input = Some(Input::FileOrStdin(file.to_owned()));
+        }
+
+        if let Some(fd) = flags.value(FD.name) {
+            if input.is_some() {
+                return Err(NoInput::Invalid(
+                    "'--fd' can't be used with a file, '--stdin', or '--input-glob'",
+                    description,
+                ));
+            }
+
+            let Some(fd) = fd.parse().ok().filter(|&fd: &i32| fd >= 0) else {
+                return Err(NoInput::Invalid(
+                    "'--fd' expects a file descriptor number, such as 3",
+                    description,
+                ));
+            };
+            input = Some(Input::Fd(fd));
+        }
+
+        if flags.is_set(CLIPBOARD.name) {
+            if input.is_some() {
+                return Err(NoInput::Invalid(
+                    "'--clipboard' can't be used with a file, '--stdin', or '--input-glob'",
+                    description,
+                ));
+            }
+            input = Some(Input::Clipboard);
+        }
+
+        if let Some(day) = flags.value(DAY.name) {
+            if input.is_some() {
+                return Err(NoInput::Invalid(
+                    "'--day' can't be used with a file, '--stdin', or '--input-glob'",
+                    description,
+                ));
+            }
+
+            let Some(day) = day.parse().ok().filter(|day| (1..=25).contains(day)) else {
+                return Err(NoInput::Invalid(
+                    "'--day' expects a day from 1 to 25",
+                    description,
+                ));
+            };
+            let year = match flags.value(YEAR.name) {
+                Some(year) => match year.parse() {
+                    Ok(year) => year,
+                    Err(_) => {
+                        return Err(NoInput::Invalid(
+                            "'--year' expects a year, such as 2022",
+                            description,
+                        ))
+                    }
+                },
+                None => aoc::DEFAULT_YEAR,
+            };
+            input = Some(Input::Aoc { year, day });
+        } else if flags.is_set(YEAR.name) {
+            return Err(NoInput::Invalid(
+                "'--year' can only be used with '--day'",
+                description,
+            ));
+        }
+
+        if input.is_none() && flags.is_set(INTERACTIVE.name) {
+            input = Some(Input::Stdin);
+        }
+
+        let standalone = description
+            .standalone_flags
+            .iter()
+            .any(|flag| flags.is_set(flag.name));
+
+        let input = match input {
+            Some(input) => input,
+            // never read, but stdin is the one input that needs no arguments
+            None if standalone => Input::Stdin,
+            None => return Err(NoInput::NoArgs(description)),
+        };
+
+        let input = if flags.is_set(CANONICALIZE.name) {
+            input.canonicalize()
+        } else {
+            input
+        };
+
+        if flags.is_set(UNTIL.name) && !input.is_stdin() {
+            return Err(NoInput::Invalid(
+                "'--until' can only be used with '--stdin'",
+                description,
+            ));
+        }
+
+        let read_options = [UNTIL, MAX_BYTES, LOSSY, DECOMPRESS];
+        if flags.is_set(PROGRESS.name) && read_options.iter().any(|flag| flags.is_set(flag.name)) {
+            return Err(NoInput::Invalid(
+                "'--progress' can't be used with '--until', '--max-bytes', '--lossy', or '--decompress'",
+                description,
+            ));
+        }
+
+        if flags.is_set(HEAD.name) && flags.is_set(TAIL.name) {
+            return Err(NoInput::Invalid(
+                "'--head' and '--tail' can't be used together",
+                description,
+            ));
+        }
+
+        if flags.is_set(WATCH.name) {
+            if !input.is_file() {
+                return Err(NoInput::Invalid(
+                    "'--watch' can only be used with a file, as other input can't be watched",
+                    description,
+                ));
+            }
+            if flags.is_set(CHECK.name) {
+                return Err(NoInput::Invalid(
+                    "'--watch' can't be used with '--check'",
+                    description,
+                ));
+            }
+        }
+
+        if flags.is_set(SPLIT.name) && flags.is_set(CHECK.name) {
+            return Err(NoInput::Invalid(
+                "'--split' can't be used with '--check'",
+                description,
+            ));
+        }
+
+        if flags.is_set(NO_COLOR.name) && flags.is_set(COLOR.name) {
+            return Err(NoInput::Invalid(
+                "'--no-color' can't be used with '--color'",
+                description,
+            ));
+        }
+
+        if flags.is_set(REPEAT.name) && !flags.is_set(TIME.name) {
+            return Err(NoInput::Invalid(
+                "'--repeat' can only be used with '--time'",
+                description,
+            ));
+        }
+
+        if flags.is_set(TEE_LINES.name) && !flags.is_set(TEE.name) {
+            return Err(NoInput::Invalid(
+                "'--tee-lines' can only be used with '--tee'",
+                description,
+            ));
+        }
+
+        let args = Self {
+            input,
+            flags,
+            standalone,
+        };
+        if args.flags.is_set(DUMP_ARGS.name) {
+            return Err(NoInput::DumpArgs(args.dump(), description));
+        }
+        Ok(args)
+    }
+
+    /// Describe the parsed arguments, one per line, with values quoted as debug strings,
+    /// so it's clear how a shell split and unquoted them.
+    fn dump(&self) -> String {
+        let Self {
+            input,
+            flags,
+            standalone,
+        } = self;
+        let color = ColorChoice::from_flags(flags)
+            .ok()
+            .flatten()
+            .unwrap_or_else(color);
+        let part = flags.value(PART.name).unwrap_or("both");
+
+        let mut dump = format!(
+            "input: {input:?}\nstandalone: {standalone}\ncolor: {color}\npart: {part}\nflags:"
+        );
+        if flags.0.is_empty() {
+            dump.push_str(" none");
+        }
+        for (name, value) in &flags.0 {
+            match value {
+                Some(value) => dump.push_str(&format!("\n    --{name} {value:?}")),
+                None => dump.push_str(&format!("\n    --{name}")),
+            }
+        }
+
+        dump
+    }
+}
+
+/// Command line arguments, with the contents of argument files spliced in.
+struct ExpandedArgs<I> {
+    args: I,
+    /// The remaining arguments read from the last argument file.
+    file: std::vec::IntoIter<String>,
+    in_file: bool,
+}
+
+impl<I: Iterator<Item = String>> ExpandedArgs<I> {
+    fn new(args: I) -> Self {
+        Self {
+            args,
+            file: Vec::new().into_iter(),
+            in_file: false,
+        }
+    }
+
+    /// Read the argument file, to take its arguments before any others.
+    fn expand(&mut self, path: &str) -> io::Result<()> {
+        let contents = fs::read_to_string(path)?;
+        let args: Vec<_> = contents.split_whitespace().map(str::to_owned).collect();
+        self.file = args.into_iter();
+        Ok(())
+    }
+
+    /// Returns `true` if the last argument was read from an argument file.
+    const fn in_file(&self) -> bool {
+        self.in_file
+    }
+}
+
+impl<I: Iterator<Item = String>> Iterator for ExpandedArgs<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(arg) = self.file.next() {
+            self.in_file = true;
+            return Some(arg);
+        }
+
+        self.in_file = false;
+        self.args.next()
+    }
+}
+
+/// The location to search for input; either a named file, a directory of files, or stdin.
+///
+/// Inputs compare equal if they name the same source the same way,
+/// so they can key a cache of what was read from them.
+/// ```
+/// use input::Input;
+/// use std::collections::HashMap;
+///
+/// let mut cache = HashMap::new();
+/// cache.insert(Input::Stdin, "from stdin");
+/// cache.insert(Input::File("-".to_owned()), "from a file named '-'");
+///
+/// assert_eq!(cache[&Input::Stdin], "from stdin");
+/// assert_eq!(cache.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Input {
+    File(String),
+    /// A directory whose files are read in order of their names, then concatenated.
+    Directory(String),
+    /// A shell style pattern whose matching files are read in order of their names,
+    /// then concatenated.
+    Glob(String),
+    Stdin,
+    /// The puzzle input of a day, fetched from adventofcode.com unless it's already cached.
+    ///
+    /// Fetching requires the `aoc` feature and a session cookie in the `AOC_SESSION` variable.
+    Aoc {
+        year: u16,
+        day: u8,
+    },
+    /// A file if it exists, otherwise stdin; which one is decided when input is read.
+    ///
+    /// Only a file that's not found falls back to stdin;
+    /// any other error reading the file, like a lack of permission, is returned.
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-file-or-stdin.txt");
+    /// fs::write(&file, "A Y\n").unwrap();
+    /// let path = file.to_string_lossy().into_owned();
+    ///
+    /// // the file is found, so its size is known
+    /// assert_eq!(Input::FileOrStdin(path.clone()).probe().unwrap(), Some(4));
+    ///
+    /// // without the file, input is read from stdin, whose size isn't known
+    /// fs::remove_file(&file).unwrap();
+    /// assert_eq!(Input::FileOrStdin(path).probe().unwrap(), None);
+    /// ```
+    FileOrStdin(String),
+    /// Input given as a string, as for tests and examples; it's never read from arguments.
+    /// ```
+    /// use input::Input;
+    ///
+    /// let input = Input::Memory("A Y\nB X\n".to_owned());
+    /// assert_eq!(input.read_to_string().unwrap(), "A Y\nB X\n");
+    /// ```
+    Memory(String),
+    /// A file descriptor inherited from the parent process, as with `3<input.txt` in a shell.
+    ///
+    /// Reading one is only supported on Unix.
+    Fd(i32),
+    /// The text on the system clipboard, as with input copied from a browser.
+    ///
+    /// Reading it requires the `clipboard` feature and a desktop session to read it from,
+    /// so over ssh or in CI there's no clipboard to read.
+    Clipboard,
+}
+
+impl Input {
+    /// Parse arguments for input source.
+    ///
+    /// # Errors
+    ///
+    /// If help information is requested, version information is requested,
+    /// or no input source is given, then [`NoInput`] is returned.
+    /// See [`Args::from_args`] for more information.
+    pub fn from_args(
+        args: impl Iterator<Item = String>,
+        description: Description,
+    ) -> Result<Self, NoInput> {
+        Args::from_args(args, description).map(|args| args.input)
+    }
+
+    /// Returns [`Self::Directory`] if the path names a directory, otherwise [`Self::File`].
+    pub fn from_path(path: String) -> Self {
+        if Path::new(&path).is_dir() {
+            Self::Directory(path)
+        } else {
+            Self::File(path)
+        }
+    }
+
+    /// Returns `true` if input is read from stdin.
+    pub const fn is_stdin(&self) -> bool {
+        matches!(self, Self::Stdin)
+    }
+
+    /// Returns `true` if input is read from a named file.
+    pub const fn is_file(&self) -> bool {
+        matches!(self, Self::File(_))
+    }
+
+    /// Returns `true` if input is read from a directory of files.
+    pub const fn is_directory(&self) -> bool {
+        matches!(self, Self::Directory(_))
+    }
+
+    /// Returns the name of the file or directory to read,
+    /// or [`None`] for a pattern, stdin, or input that's only decided when read.
+    pub const fn path(&self) -> Option<&str> {
+        match self {
+            Self::File(path) | Self::Directory(path) => Some(path.as_str()),
+            Self::Glob(_)
+            | Self::Stdin
+            | Self::Aoc { .. }
+            | Self::FileOrStdin(_)
+            | Self::Memory(_)
+            | Self::Fd(_)
+            | Self::Clipboard => None,
+        }
+    }
+
+    /// Replace the path of a file or directory with its absolute form, with symlinks resolved,
+    /// so errors name the file that was actually read.
+    ///
+    /// If the path can't be resolved, for example because it doesn't exist, it's left as is.
+    #[must_use]
+    pub fn canonicalize(self) -> Self {
+        let canonical = |path: &str| match fs::canonicalize(path) {
+            Ok(canonical) => canonical.to_string_lossy().into_owned(),
+            Err(_) => path.to_owned(),
+        };
+
+        match self {
+            Self::File(ref file) => Self::File(canonical(file)),
+            Self::Directory(ref directory) => Self::Directory(canonical(directory)),
+            Self::FileOrStdin(ref file) => Self::FileOrStdin(canonical(file)),
+            Self::Glob(_)
+            | Self::Stdin
+            | Self::Aoc { .. }
+            | Self::Memory(_)
+            | Self::Fd(_)
+            | Self::Clipboard => self,
+        }
+    }
+
+    /// Returns the size of the input in bytes without reading it,
+    /// or [`None`] for stdin or puzzle input to fetch.
+    ///
+    /// The size of a directory or pattern is the total size of the files that would be read.
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-probe.txt");
+    /// fs::write(&file, "1000\n2000\n").unwrap();
+    ///
+    /// let input = Input::File(file.to_string_lossy().into_owned());
+    /// assert_eq!(input.probe().unwrap(), Some(10));
+    /// assert_eq!(Input::Stdin.probe().unwrap(), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the metadata of a file can't be read, then [`IoError`] is returned.
+    pub fn probe(&self) -> Result<Option<u64>, IoError> {
+        let paths = match self {
+            Self::File(file) => vec![path::long(file).into_owned()],
+            Self::Directory(directory) => directory_paths(directory)?,
+            Self::Glob(pattern) => glob_paths(pattern)?,
+            Self::Stdin | Self::Aoc { .. } | Self::Fd(_) | Self::Clipboard => return Ok(None),
+            Self::FileOrStdin(_) => return self.clone().resolve()?.probe(),
+            Self::Memory(contents) => return Ok(Some(contents.len() as u64)),
+        };
+
+        let mut size = 0;
+        for path in paths {
+            let metadata = fs::metadata(&path).map_err(|error| IoError {
+                input: Input::File(path.to_string_lossy().into_owned()),
+                error,
+            })?;
+            if metadata.is_file() || self.is_file() {
+                size += metadata.len();
+            }
+        }
+
+        Ok(Some(size))
+    }
+
+    /// Returns a [`String`] containing the input collected from standard input or a file.
+    ///
+    /// Every regular file of a directory is read in order of name, then concatenated;
+    /// any other entries are skipped with a note on stderr.
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while reading input from stdin or a file,
+    /// then [`io::Error`] is returned.
+    /// See [`fs::read_to_string`] and [`io::read_to_string`] for more information.
+    pub fn read_to_string(self) -> Result<String, IoError> {
+        match self {
+            Self::File(ref file) => fs::read_to_string(path::long(file)),
+            Self::Directory(ref directory) => {
+                return read_directory(directory, ReadOptions::default());
+            }
+            Self::Glob(ref pattern) => return read_glob(pattern, ReadOptions::default()),
+            Self::Stdin => io::read_to_string(stdin_locked()),
+            Self::Aoc { .. } | Self::FileOrStdin(_) => return self.resolve()?.read_to_string(),
+            Self::Memory(contents) => return Ok(contents),
+            Self::Fd(fd) => fd::open(fd).and_then(io::read_to_string),
+            Self::Clipboard => clipboard::read(),
+        }
+        .map_err(|error| IoError { input: self, error })
+    }
+
+    /// Returns a [`String`] containing the input, like [`Self::read_to_string`],
+    /// reading stdin or a file descriptor `capacity` bytes at a time.
+    ///
+    /// A larger buffer means fewer reads of input that's piped in, like one enormous line.
+    /// The default of [`io::BufReader`] is 8 KiB; 64 KiB, the size of a pipe buffer on Linux,
+    /// suits most piped input, and more than a few MiB rarely reads any faster.
+    /// A capacity of 0 is treated as 1. Files are read in one go, so they ignore the capacity.
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-buffered.txt");
+    /// fs::write(&file, "1000\n2000\n").unwrap();
+    ///
+    /// let input = Input::File(file.to_string_lossy().into_owned());
+    /// assert_eq!(input.read_to_string_buffered(64 * 1024).unwrap(), "1000\n2000\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As with [`Self::read_to_string`], if an error is encountered while reading input,
+    /// then [`IoError`] is returned.
+    pub fn read_to_string_buffered(self, capacity: usize) -> Result<String, IoError> {
+        match self {
+            Self::Stdin => read_buffered(io::stdin(), capacity),
+            Self::Fd(fd) => fd::open(fd).and_then(|file| read_buffered(file, capacity)),
+            Self::Aoc { .. } | Self::FileOrStdin(_) => {
+                return self.resolve()?.read_to_string_buffered(capacity);
+            }
+            _ => return self.read_to_string(),
+        }
+        .map_err(|error| IoError { input: self, error })
+    }
+
+    /// Returns a [`String`] containing the input, failing if it's larger than `max_bytes`.
+    ///
+    /// Input is never read much past the limit, so huge input can't exhaust memory.
+    /// ```
+    /// use input::Input;
+    /// use std::io::ErrorKind;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-limit.txt");
+    /// fs::write(&file, "1000\n2000\n").unwrap();
+    ///
+    /// let input = Input::File(file.to_string_lossy().into_owned());
+    /// assert_eq!(input.clone().read_to_string_with_limit(10).unwrap(), "1000\n2000\n");
+    ///
+    /// let error = input.read_to_string_with_limit(9).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::FileTooLarge);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the input is too large, then [`IoError`] is returned,
+    /// wrapping an [`InputTooLarge`] error of kind [`io::ErrorKind::FileTooLarge`].
+    /// As with [`Self::read_to_string`], any other error reading input is returned as well.
+    pub fn read_to_string_with_limit(self, max_bytes: u64) -> Result<String, IoError> {
+        self.read_with(ReadOptions {
+            max_bytes: Some(max_bytes),
+            ..ReadOptions::default()
+        })
+    }
+
+    /// Returns a [`String`] containing the input, reporting progress while it's read.
+    ///
+    /// Input is read in chunks of [`PROGRESS_CHUNK`] bytes, and after each one,
+    /// `report` is called with the number of bytes read so far,
+    /// and the total size from [`Self::probe`], which is [`None`] for stdin.
+    /// A last chunk may be smaller, so `report` is called once per chunk, and at least once.
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-progress.txt");
+    /// fs::write(&file, "1000\n".repeat(20_000)).unwrap();
+    ///
+    /// let mut reports = Vec::new();
+    /// let input = Input::File(file.to_string_lossy().into_owned());
+    /// let input = input.read_to_string_progress(|read, total| reports.push((read, total)));
+    ///
+    /// assert_eq!(input.unwrap().len(), 100_000);
+    /// assert_eq!(reports, [(65_536, Some(100_000)), (100_000, Some(100_000))]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while reading input, then [`IoError`] is returned.
+    pub fn read_to_string_progress(
+        self,
+        mut report: impl FnMut(u64, Option<u64>),
+    ) -> Result<String, IoError> {
+        let total = self.probe()?;
+        let mut reader = self.reader()?;
+
+        let mut bytes = Vec::new();
+        let read = loop {
+            let chunk = (&mut reader).take(PROGRESS_CHUNK).read_to_end(&mut bytes);
+            match chunk {
+                Ok(read) => {
+                    report(bytes.len() as u64, total);
+                    if read < PROGRESS_CHUNK as usize {
+                        break Ok(bytes);
+                    }
+                }
+                Err(error) => break Err(error),
+            }
+        };
+        drop(reader);
+
+        read.and_then(|bytes| decode(bytes, false))
+            .map_err(|error| IoError { input: self, error })
+    }
+
+    /// Returns an iterator over the lines of input, reading them as they're needed.
+    ///
+    /// Stdin stays locked while the iterator is alive, and is unlocked once it's dropped.
+    /// A directory is read in full up front, as with [`Self::read_to_string`].
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-lines.txt");
+    /// fs::write(&file, "a\nb\n").unwrap();
+    ///
+    /// let input = Input::File(file.to_string_lossy().into_owned());
+    /// let lines: Result<Vec<_>, _> = input.lines().unwrap().collect();
+    /// assert_eq!(lines.unwrap(), ["a", "b"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the input can't be opened, then [`IoError`] is returned,
+    /// and likewise for each line that can't be read.
+    pub fn lines(self) -> Result<Lines, IoError> {
+        let reader = self.reader()?;
+        Ok(Lines {
+            input: self,
+            lines: reader.lines(),
+        })
+    }
+
+    /// Returns a [`String`] containing the input, replacing invalid utf-8 with `U+FFFD`.
+    ///
+    /// Unlike [`Self::read_to_string`], input that isn't valid utf-8 is not an error.
+    /// See [`String::from_utf8_lossy`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while reading input, then [`IoError`] is returned.
+    pub fn read_to_string_lossy(self) -> Result<String, IoError> {
+        self.read_with(ReadOptions {
+            lossy: true,
+            ..ReadOptions::default()
+        })
+    }
+
+    /// Returns a [`String`] containing the input up to the first line equal to `marker`.
+    ///
+    /// Reading stops at the marker, which isn't included, like the limit string of a heredoc.
+    /// Without a marker line, all input is returned.
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-read-until.txt");
+    /// fs::write(&file, "a\nb\nEND\nc\n").unwrap();
+    ///
+    /// let input = Input::File(file.to_string_lossy().into_owned());
+    /// assert_eq!(input.read_until("END").unwrap(), "a\nb\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while reading input, then [`IoError`] is returned.
+    pub fn read_until(self, marker: &str) -> Result<String, IoError> {
+        self.read_with(ReadOptions {
+            until: Some(marker),
+            ..ReadOptions::default()
+        })
+    }
+
+    fn read_with(self, options: ReadOptions<'_>) -> Result<String, IoError> {
+        if let Self::Aoc { .. } | Self::FileOrStdin(_) = self {
+            return self.resolve()?.read_with(options);
+        }
+
+        if options == ReadOptions::default() {
+            return self.read_to_string();
+        }
+
+        // check the size of an uncompressed file up front, to avoid reading any of it
+        if let (Self::File(file), Some(max_bytes), Compression::None) =
+            (&self, options.max_bytes, options.decompress)
+        {
+            if fs::metadata(path::long(file)).is_ok_and(|metadata| metadata.len() > max_bytes) {
+                let error = InputTooLarge { max_bytes }.into();
+                return Err(IoError { input: self, error });
+            }
+        }
+
+        let options = match self {
+            Self::File(ref file) => ReadOptions {
+                decompress: options.decompress.for_path(Path::new(file)),
+                ..options
+            },
+            _ => options,
+        };
+
+        let input = match self {
+            Self::File(_) | Self::Stdin | Self::Memory(_) | Self::Fd(_) | Self::Clipboard => {
+                let reader = self.reader()?;
+                return read_source(reader, options)
+                    .map_err(|error| IoError { input: self, error });
+            }
+            Self::Directory(ref directory) => read_directory(directory, options)?,
+            Self::Glob(ref pattern) => read_glob(pattern, options)?,
+            Self::Aoc { .. } | Self::FileOrStdin(_) => {
+                unreachable!("input is resolved to a file or stdin above")
+            }
+        };
+
+        // each file has already been decompressed and counted against the limit on its own
+        let options = ReadOptions {
+            decompress: Compression::None,
+            max_bytes: None,
+            ..options
+        };
+        read_source(input.as_bytes(), options).map_err(|error| IoError { input: self, error })
+    }
+
+    /// Returns the contents of each file of input separately, in the order they'd be concatenated.
+    ///
+    /// A file or stdin is returned as the only element.
+    ///
+    /// # Errors
+    ///
+    /// If an error is encountered while reading input, then [`IoError`] is returned,
+    /// naming the file that couldn't be read.
+    pub fn read_each(self) -> Result<Vec<InputFile>, IoError> {
+        self.read_each_with(ReadOptions::default())
+    }
+
+    fn read_each_with(self, options: ReadOptions<'_>) -> Result<Vec<InputFile>, IoError> {
+        let paths = match self {
+            Self::Directory(ref directory) => directory_paths(directory)?,
+            Self::Glob(ref pattern) => glob_paths(pattern)?,
+            Self::File(_)
+            | Self::Stdin
+            | Self::Aoc { .. }
+            | Self::FileOrStdin(_)
+            | Self::Memory(_)
+            | Self::Fd(_)
+            | Self::Clipboard => {
+                let input = self.resolve()?;
+                let contents = input.clone().read_with(options)?;
+                return Ok(vec![InputFile { input, contents }]);
+            }
+        };
+
+        read_files(paths, options)
+    }
+
+    /// Open the input as a single buffered handle, which for stdin holds its lock until dropped.
+    fn reader(&self) -> Result<Box<dyn BufRead>, IoError> {
+        match self {
+            Self::File(file) => match fs::File::open(path::long(file)) {
+                Ok(file) => Ok(Box::new(io::BufReader::new(file))),
+                Err(error) => Err(IoError {
+                    input: self.clone(),
+                    error,
+                }),
+            },
+            Self::Directory(directory) => {
+                let input = read_directory(directory, ReadOptions::default())?;
+                Ok(Box::new(io::Cursor::new(input.into_bytes())))
+            }
+            Self::Glob(pattern) => {
+                let input = read_glob(pattern, ReadOptions::default())?;
+                Ok(Box::new(io::Cursor::new(input.into_bytes())))
+            }
+            Self::Stdin => Ok(Box::new(stdin_locked())),
+            Self::Memory(contents) => Ok(Box::new(io::Cursor::new(contents.clone().into_bytes()))),
+            Self::Fd(fd) => match fd::open(*fd) {
+                Ok(file) => Ok(Box::new(io::BufReader::new(file))),
+                Err(error) => Err(IoError {
+                    input: self.clone(),
+                    error,
+                }),
+            },
+            Self::Clipboard => match clipboard::read() {
+                Ok(text) => Ok(Box::new(io::Cursor::new(text.into_bytes()))),
+                Err(error) => Err(IoError {
+                    input: self.clone(),
+                    error,
+                }),
+            },
+            Self::Aoc { .. } | Self::FileOrStdin(_) => self.clone().resolve()?.reader(),
+        }
+    }
+
+    /// Decide on the file or stdin to read input that's only decided when read;
+    /// puzzle input is replaced with the file it's cached in, fetching it first if needed.
+    fn resolve(self) -> Result<Self, IoError> {
+        match self {
+            Self::Aoc { year, day } => match aoc::cached(year, day) {
+                Ok(path) => Ok(Self::File(path.to_string_lossy().into_owned())),
+                Err(error) => Err(IoError { input: self, error }),
+            },
+            Self::FileOrStdin(file) => match fs::metadata(path::long(&file)) {
+                Ok(_) => Ok(Self::File(file)),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::Stdin),
+                Err(error) => Err(IoError {
+                    input: Self::File(file),
+                    error,
+                }),
+            },
+            _ => Ok(self),
+        }
+    }
+}
+
+/// Describes where input is read from, as in messages about it.
+/// ```
+/// use input::Input;
+///
+/// assert_eq!(Input::File("input.txt".to_owned()).to_string(), "file 'input.txt'");
+/// assert_eq!(Input::Memory("A Y\n".to_owned()).to_string(), "in-memory input");
+/// ```
+impl Display for Input {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(file) => write!(f, "file '{}'", path::display(file)),
+            Self::Directory(directory) => write!(f, "directory '{directory}'"),
+            Self::Glob(pattern) => write!(f, "files matching '{pattern}'"),
+            Self::Stdin => write!(f, "stdin"),
+            Self::Aoc { year, day } => write!(f, "the input of day {day} of {year}"),
+            Self::FileOrStdin(file) => write!(f, "file '{}' or stdin", path::display(file)),
+            Self::Memory(_) => write!(f, "in-memory input"),
+            Self::Fd(fd) => write!(f, "file descriptor {fd}"),
+            Self::Clipboard => write!(f, "the clipboard"),
+        }
+    }
+}
+
+/// The number of bytes read between each report of [`Input::read_to_string_progress`].
+pub const PROGRESS_CHUNK: u64 = 64 * 1024;
+
+/// Lock stdin once, so all reads through the handle share the lock instead of retaking it.
+fn stdin_locked() -> io::StdinLock<'static> {
+    io::stdin().lock()
+}
+
+/// An iterator over the lines of input, returned by [`Input::lines`].
+pub struct Lines {
+    input: Input,
+    lines: io::Lines<Box<dyn BufRead>>,
+}
+
+impl Iterator for Lines {
+    type Item = Result<String, IoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        Some(line.map_err(|error| IoError {
+            input: self.input.clone(),
+            error,
+        }))
+    }
+}
+
+impl fmt::Debug for Lines {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lines")
+            .field("input", &self.input)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Options controlling how input is read, as set by the builtin flags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ReadOptions<'a> {
+    /// Stop reading at the first line equal to this marker.
+    until: Option<&'a str>,
+    /// Replace invalid utf-8 instead of returning an error.
+    lossy: bool,
+    /// Decompress input before decoding it.
+    decompress: Compression,
+    /// Fail instead of reading more than this many bytes, after decompressing.
+    max_bytes: Option<u64>,
+}
+
+impl<'a> ReadOptions<'a> {
+    fn from_flags(flags: &'a Flags) -> Result<Self, FlagError> {
+        Ok(Self {
+            until: flags.value(UNTIL.name),
+            lossy: flags.is_set(LOSSY.name),
+            decompress: flags.parse(DECOMPRESS.name)?.unwrap_or_default(),
+            max_bytes: flags.parse(MAX_BYTES.name)?,
+        })
+    }
+}
+
+fn read_source(reader: impl BufRead, options: ReadOptions<'_>) -> io::Result<String> {
+    read_source_counted(reader, options).map(|(input, _)| input)
+}
+
+/// Read input like [`read_source`], also returning the number of bytes read after decompressing,
+/// which differs from the length of the text if invalid utf-8 was replaced.
+fn read_source_counted(
+    reader: impl BufRead,
+    options: ReadOptions<'_>,
+) -> io::Result<(String, u64)> {
+    let reader = options.decompress.decoder(reader)?;
+    // read one byte past the limit, to tell input exactly at the limit from larger input
+    let mut reader: Box<dyn BufRead> = match options.max_bytes {
+        Some(max_bytes) => Box::new(reader.take(max_bytes.saturating_add(1))),
+        None => reader,
+    };
+
+    let bytes = match options.until {
+        Some(marker) => read_lines_until(reader, marker)?,
+        None => {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            bytes
+        }
+    };
+
+    let read = bytes.len() as u64;
+    if let Some(max_bytes) = options.max_bytes {
+        if read > max_bytes {
+            return Err(InputTooLarge { max_bytes }.into());
+        }
+    }
+
+    decode(bytes, options.lossy).map(|input| (input, read))
+}
+
+/// Read all of the reader through a buffer of `capacity` bytes, so each read asks for that many.
+fn read_buffered(reader: impl Read, capacity: usize) -> io::Result<String> {
+    let mut reader = io::BufReader::with_capacity(capacity.max(1), reader);
+    let mut bytes = Vec::new();
+
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return decode(bytes, false);
+        }
+
+        bytes.extend_from_slice(buffer);
+        let len = buffer.len();
+        reader.consume(len);
+    }
+}
+
+fn read_lines_until(mut reader: impl BufRead, marker: &str) -> io::Result<Vec<u8>> {
+    let mut input = Vec::new();
+
+    loop {
+        let start = input.len();
+        if reader.read_until(b'\n', &mut input)? == 0 {
+            return Ok(input);
+        }
+
+        let line = &input[start..];
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line == marker.as_bytes() {
+            input.truncate(start);
+            return Ok(input);
+        }
+    }
+}
+
+fn decode(bytes: Vec<u8>, lossy: bool) -> io::Result<String> {
+    match String::from_utf8(bytes) {
+        Ok(input) => Ok(input),
+        Err(error) if lossy => Ok(String::from_utf8_lossy(error.as_bytes()).into_owned()),
+        Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+    }
+}
+
+/// Read every file of the directory in order of name, applying the options to each file.
+///
+/// The marker of [`ReadOptions::until`] is ignored, as it applies to the concatenated input.
+fn read_directory(directory: &str, options: ReadOptions<'_>) -> Result<String, IoError> {
+    let files = read_files(directory_paths(directory)?, options)?;
+    Ok(concat(files))
+}
+
+/// Read every file matching the pattern, in the same way as [`read_directory`].
+fn read_glob(pattern: &str, options: ReadOptions<'_>) -> Result<String, IoError> {
+    let files = read_files(glob_paths(pattern)?, options)?;
+    Ok(concat(files))
+}
+
+fn concat(files: Vec<InputFile>) -> String {
+    files.into_iter().map(|file| file.contents).collect()
+}
+
+fn directory_paths(directory: &str) -> Result<Vec<PathBuf>, IoError> {
+    fs::read_dir(directory)
+        .and_then(|entries| entries.map(|entry| Ok(entry?.path())).collect())
+        .map_err(|error| IoError {
+            input: Input::Directory(directory.to_owned()),
+            error,
+        })
+}
+
+fn glob_paths(pattern: &str) -> Result<Vec<PathBuf>, IoError> {
+    let glob_error = |error| IoError {
+        input: Input::Glob(pattern.to_owned()),
+        error,
+    };
+
+    let paths: Vec<_> = glob::glob(pattern)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+        .and_then(|paths| paths.map(|path| path.map_err(io::Error::from)).collect())
+        .map_err(glob_error)?;
+
+    if paths.is_empty() {
+        let error = io::Error::new(io::ErrorKind::NotFound, "no files match the pattern");
+        return Err(glob_error(error));
+    }
+
+    Ok(paths)
+}
+
+/// Read every regular file in order of name, skipping anything else.
+fn read_files(
+    mut paths: Vec<PathBuf>,
+    options: ReadOptions<'_>,
+) -> Result<Vec<InputFile>, IoError> {
+    let options = ReadOptions {
+        until: None,
+        ..options
+    };
+    paths.sort_unstable();
+
+    let mut files = Vec::new();
+    let mut remaining = options.max_bytes;
+    for path in paths {
+        let file = path.to_string_lossy().into_owned();
+
+        if !path.is_file() {
+            note(format_args!("skipping '{file}', not a file"));
+            continue;
+        }
+
+        // the limit applies to all files together
+        let file_options = ReadOptions {
+            max_bytes: remaining,
+            decompress: options.decompress.for_path(&path),
+            ..options
+        };
+        let (contents, read) = fs::File::open(&path)
+            .and_then(|file| read_source_counted(io::BufReader::new(file), file_options))
+            .map_err(|error| {
+                let error = match options.max_bytes {
+                    Some(max_bytes) if error.kind() == io::ErrorKind::FileTooLarge => {
+                        InputTooLarge { max_bytes }.into()
+                    }
+                    _ => error,
+                };
+                IoError {
+                    input: Input::File(file.clone()),
+                    error,
+                }
+            })?;
+        // the bytes read count against the limit, which replacing invalid utf-8 can outgrow
+        remaining = remaining.map(|remaining| remaining - read);
+        files.push(InputFile {
+            input: Input::File(file),
+            contents,
+        });
+    }
+
+    Ok(files)
+}
+
+/// The contents of a single file of input, or of stdin.
+#[derive(Debug, Clone)]
+pub struct InputFile {
+    pub input: Input,
+    pub contents: String,
+}
+
+impl InputFile {
+    /// Returns the name of the file, or `stdin`, `in-memory input`, `file descriptor`,
+    /// or `clipboard`.
+    pub fn name(&self) -> &str {
+        match self.input {
+            Input::Memory(_) => "in-memory input",
+            Input::Fd(_) => "file descriptor",
+            Input::Clipboard => "clipboard",
+            _ => self.input.path().unwrap_or("stdin"),
+        }
+    }
+}
+
+/// An error returned when no input source is specified.
+///
+/// An unknown long option that's a couple of edits away from a known one suggests it:
+/// ```
+/// use input::{Args, Description};
+///
+/// let description = || Description {
+///     name: "app",
+///     bin_name: "app".into(),
+///     description: "",
+///     version: (0, 0, 0),
+///     flags: &[],
+///     standalone_flags: &[],
+///     env_vars: &[],
+///     examples: &[],
+/// };
+/// let args = |arg: &str| ["app", arg].map(String::from).into_iter();
+///
+/// let error = Args::from_args(args("--stdn"), description()).unwrap_err();
+/// let message = error.to_string();
+/// let first_line = message.lines().next().unwrap();
+/// assert_eq!(first_line, "unexpected argument '--stdn' found; did you mean '--stdin'?");
+///
+/// let error = Args::from_args(args("--frobnicate"), description()).unwrap_err();
+/// let message = error.to_string();
+/// let first_line = message.lines().next().unwrap();
+/// assert_eq!(first_line, "unexpected argument '--frobnicate' found");
+/// ```
+#[derive(Debug, Clone)]
+pub enum NoInput {
+    /// No valid arguments have been found.
+    NoArgs(Box<Description>),
+    /// Full help text has been requested with `--help`.
+    ///
+    /// The help lists the sources input can be read from,
+    /// with only those this build supports.
+    /// ```
+    /// use input::{Args, Compression, Description, NoInput};
+    ///
+    /// let description = Description {
+    ///     name: "app",
+    ///     bin_name: "app".into(),
+    ///     description: "",
+    ///     version: (0, 0, 0),
+    ///     flags: &[],
+    ///     standalone_flags: &[],
+    ///     env_vars: &[],
+    ///     examples: &[],
+    /// };
+    /// let args = ["app", "--help"].map(String::from);
+    ///
+    /// let help = Args::from_args(args.into_iter(), description).unwrap_err();
+    /// assert!(matches!(help, NoInput::Help(_)));
+    /// let help = help.to_string();
+    /// let (_, sources) = help.split_once("Supported input sources:").unwrap();
+    /// assert!(sources.contains("Stdin"));
+    /// assert_eq!(sources.contains("gzip"), Compression::Gzip.is_supported());
+    /// ```
+    Help(Box<Description>),
+    /// A summary of help text has been requested with `-h`.
+    ShortHelp(Box<Description>),
+    /// Version information has been requested.
+    Version(Box<Description>),
+    /// Version information has been requested as json with `--version-json`,
+    /// for release tooling to parse.
+    /// ```
+    /// use input::{Args, Description, NoInput};
+    ///
+    /// let description = Description {
+    ///     name: "app",
+    ///     bin_name: "app".into(),
+    ///     description: "",
+    ///     version: (1, 2, 3),
+    ///     flags: &[],
+    ///     standalone_flags: &[],
+    ///     env_vars: &[],
+    ///     examples: &[],
+    /// };
+    /// let args = ["app", "--version-json"].map(String::from);
+    ///
+    /// let error = Args::from_args(args.into_iter(), description).unwrap_err();
+    /// assert!(matches!(error, NoInput::VersionJson(_)));
+    /// assert_eq!(error.to_string(), r#"{"name": "app", "version": "1.2.3"}"#);
+    /// ```
+    VersionJson(Box<Description>),
+    /// An unknown option or a superfluous argument has been found.
+    Unexpected(String, Box<Description>),
+    /// A flag requiring a value was the last argument.
+    MissingValue(&'static Flag, Box<Description>),
+    /// The arguments are valid individually, but not in combination.
+    Invalid(&'static str, Box<Description>),
+    /// An argument file couldn't be read.
+    ArgFile(String, io::ErrorKind, Box<Description>),
+    /// How the arguments were parsed has been requested with the hidden `--dump-args` flag,
+    /// for debugging quoting in a shell; the dump is displayed on stderr.
+    /// ```
+    /// use input::{Args, Description, NoInput};
+    ///
+    /// let description = Description {
+    ///     name: "app",
+    ///     bin_name: "app".into(),
+    ///     description: "",
+    ///     version: (0, 0, 0),
+    ///     flags: &[],
+    ///     standalone_flags: &[],
+    ///     env_vars: &[],
+    ///     examples: &[],
+    /// };
+    /// let args = ["app", "--dump-args", "--split", "- -", "input.txt"].map(String::from);
+    ///
+    /// let Err(NoInput::DumpArgs(dump, _)) = Args::from_args(args.into_iter(), description) else {
+    ///     panic!("the arguments should have been dumped");
+    /// };
+    /// assert!(dump.starts_with("input: File(\"input.txt\")\n"));
+    /// assert!(dump.ends_with("\n    --split \"- -\""));
+    /// ```
+    DumpArgs(String, Box<Description>),
+}
+
+impl NoInput {
+    /// Return the app metadata, ignoring the error cause.
+    pub const fn description(&self) -> &Description {
+        match self {
+            Self::NoArgs(description)
+            | Self::Help(description)
+            | Self::ShortHelp(description)
+            | Self::Version(description)
+            | Self::VersionJson(description)
+            | Self::Unexpected(_, description)
+            | Self::MissingValue(_, description)
+            | Self::Invalid(_, description)
+            | Self::ArgFile(_, _, description)
+            | Self::DumpArgs(_, description) => description,
+        }
+    }
+
+    /// Display help or version information, or the dump of `--dump-args`, then exit.
+    /// Does nothing with [`Self::NoArgs`].
+    #[must_use]
+    pub fn display_help(self) -> Self {
+        if let Self::DumpArgs(ref dump, _) = self {
+            eprintln!("{dump}");
+            process::exit(0);
+        }
+
+        if let Self::VersionJson(_) = self {
+            // json is for programs to read, so it's never colored
+            let _ = writeln!(io::stdout(), "{self}");
+            process::exit(0);
+        }
+
+        if let Self::Help(_) | Self::ShortHelp(_) | Self::Version(_) = self {
+            let help = self.to_string();
+            // help piped into a pager that quits early is still displayed successfully
+            let _ = if color().enabled(&io::stdout()) {
+                writeln!(io::stdout(), "{}", color::paint_help(&help))
+            } else {
+                writeln!(io::stdout(), "{help}")
+            };
+            process::exit(0);
+        }
+
+        self
+    }
+}
+
+impl Error for NoInput {}
+
+impl Display for NoInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Description {
+            name,
+            bin_name,
+            description,
+            flags,
+            standalone_flags,
+            env_vars,
+            examples,
+            ..
+        } = self.description();
+        let version = self.description().version_string();
+
+        match self {
+            Self::NoArgs(_) => write!(
+                f,
+                "\
+The following required argument was not provided: <FILE>
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+            ),
+            Self::Unexpected(arg, description) => {
+                write!(f, "unexpected argument '{arg}' found")?;
+                if let Some(option) = description.suggestion(arg) {
+                    write!(f, "; did you mean '--{option}'?")?;
+                }
+                write!(
+                    f,
+                    "
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+                )
+            }
+            Self::MissingValue(flag, _) => write!(
+                f,
+                "\
+a value is required for '{flag}' but none was supplied
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+            ),
+            Self::ArgFile(path, io::ErrorKind::NotFound, _) => write!(
+                f,
+                "\
+argument file '{path}' does not exist
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+            ),
+            Self::ArgFile(path, kind, _) => write!(
+                f,
+                "\
+can't read argument file '{path}': {kind}
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+            ),
+            Self::Invalid(message, _) => write!(
+                f,
+                "\
+{message}
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+            ),
+            Self::ShortHelp(description) => {
+                let summary = description.summary().unwrap_or_default();
+                write!(
+                    f,
+                    "\
+{name} {version}
+{summary}
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+For more information try '--help'"
+                )
+            }
+            Self::Help(_) => {
+                write!(
+                    f,
+                    "\
+{name} {version}
+Solution app for advent of code 2022.
+{description}
+
+Usage: {bin_name} [OPTIONS] [FILE]
+
+Args:
+    <FILE>     File, or directory of files, to read as input
+    @<ARGS>    Read more arguments from the file ARGS, separated by whitespace
+
+Options:"
+                )?;
+
+                let width = help_width();
+                write_options(f, flags, width)?;
+
+                if !standalone_flags.is_empty() {
+                    let standalone_flags: Vec<_> = standalone_flags
+                        .iter()
+                        .map(|flag| (format!("    {flag}"), flag.help))
+                        .collect();
+
+                    write!(f, "\n\nWithout input:")?;
+                    write_table(f, &standalone_flags, width)?;
+                }
+
+                write!(f, "\n\nSupported input sources:")?;
+                write_table(f, &input_sources(), width)?;
+
+                let env_vars: Vec<_> = builtin_env_vars()
+                    .into_iter()
+                    .chain(env_vars.iter().copied())
+                    .map(|(name, help)| (name.to_owned(), help))
+                    .collect();
+
+                write!(f, "\n\nEnvironment:")?;
+                write_table(f, &env_vars, width)?;
+
+                if !examples.is_empty() {
+                    write!(f, "\n\nExamples:")?;
+                    for example in *examples {
+                        write!(f, "\n    {example}")?;
+                    }
+                }
+
+                Ok(())
+            }
+            Self::Version(_) => write!(f, "{name} {version}"),
+            Self::VersionJson(_) => {
+                let mut json = String::from("{\"name\": ");
+                error_format::push_json_string(&mut json, Some(name.to_string()));
+                json.push_str(", \"version\": ");
+                error_format::push_json_string(&mut json, Some(version));
+                json.push('}');
+                f.write_str(&json)
+            }
+            Self::DumpArgs(dump, _) => f.write_str(dump),
+        }
+    }
+}
+
+/// Returns the width to wrap help information to.
+///
+/// Uses the `COLUMNS` environment variable when stdout is a terminal,
+/// falling back to 80 columns so piped output is stable.
+fn help_width() -> usize {
+    const DEFAULT: usize = 80;
+
+    if !io::stdout().is_terminal() {
+        return DEFAULT;
+    }
+
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+        .unwrap_or(DEFAULT)
+}
+
+/// Returns the environment variables the framework itself reads in this build,
+/// leaving out those of features it wasn't built with.
+fn builtin_env_vars() -> Vec<(&'static str, &'static str)> {
+    let env_vars = [
+        (
+            true,
+            "NO_COLOR",
+            "Don't color help and errors with '--color auto', unless empty",
+        ),
+        (
+            true,
+            "COLUMNS",
+            "The width to wrap help to, when printed to a terminal",
+        ),
+        (
+            cfg!(feature = "aoc"),
+            "AOC_SESSION",
+            "The adventofcode.com session cookie, to fetch input with '--day'",
+        ),
+        (
+            cfg!(feature = "aoc"),
+            "XDG_CACHE_HOME",
+            "Where input fetched with '--day' is cached, instead of '~/.cache'",
+        ),
+    ];
+
+    env_vars
+        .into_iter()
+        .filter(|&(supported, _, _)| supported)
+        .map(|(_, name, help)| (name, help))
+        .collect()
+}
+
+/// Returns the sources input can be read from in this build,
+/// leaving out those that need a feature or platform it wasn't built with.
+fn input_sources() -> Vec<(String, &'static str)> {
+    let sources = [
+        (
+            true,
+            "Files",
+            "A file, every file of a directory, or files matching '--input-glob'",
+        ),
+        (
+            true,
+            "Stdin",
+            "Input piped in with '--stdin' or '--input-or-stdin'",
+        ),
+        (
+            cfg!(unix),
+            "File descriptors",
+            "A file descriptor inherited with '--fd'",
+        ),
+        (
+            cfg!(feature = "compression"),
+            "gzip, zstd, bzip2",
+            "Compressed input, decompressed with '--decompress'",
+        ),
+        (
+            cfg!(feature = "aoc"),
+            "adventofcode.com",
+            "Puzzle input fetched with '--day', then cached",
+        ),
+        (
+            cfg!(feature = "clipboard"),
+            "Clipboard",
+            "Text copied to the clipboard, read with '--clipboard'",
+        ),
+    ];
+
+    sources
+        .into_iter()
+        .filter(|&(supported, _, _)| supported)
+        .map(|(_, source, help)| (source.to_owned(), help))
+        .collect()
+}
+
+fn write_options(f: &mut Formatter<'_>, flags: &[Flag], width: usize) -> fmt::Result {
+    let builtin = [
+        (
+            "-h, --help".to_owned(),
+            "Print help information; a summary with '-h'",
+        ),
+        ("-V, --version".to_owned(), "Print version information"),
+        (
+            "    --version-json".to_owned(),
+            "Print version information as json, with its name and version",
+        ),
+        (
+            "-0  --stdin".to_owned(),
+            "Read input from stdin instead of a file",
+        ),
+    ];
+    let options = BUILTIN_FLAGS.iter().chain(flags);
+    let options = options.map(|flag| (format!("    {flag}"), flag.help));
+    let options: Vec<_> = builtin.into_iter().chain(options).collect();
+
+    write_table(f, &options, width)
+}
+
+/// Write each name and its help text on its own line, with the help texts aligned and wrapped.
+fn write_table(f: &mut Formatter<'_>, rows: &[(String, &str)], width: usize) -> fmt::Result {
+    const INDENT: usize = 4;
+    const GAP: usize = 4;
+    const MIN_HELP_WIDTH: usize = 20;
+
+    let column = rows.iter().map(|(name, _)| name.len()).max();
+    let column = column.unwrap_or_default() + GAP;
+    let help_width = width.saturating_sub(INDENT + column).max(MIN_HELP_WIDTH);
+
+    for (name, help) in rows {
+        let lines = wrap(help, help_width);
+        let mut lines = lines.iter();
+        let first = lines.next().map_or("", String::as_str);
+        write!(f, "\n{:INDENT$}{name:column$}{first}", "")?;
+
+        for line in lines {
+            write!(f, "\n{:indent$}{line}", "", indent = INDENT + column)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Split text into lines no longer than `width`, breaking only between words.
+///
+/// Words longer than `width` are put on a line of their own rather than split.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// An error wrapping [`io::Error`] with more context.
+///
+/// For files and directories, common kinds of errors include a hint about the cause.
+/// ```
+/// use input::{Input, IoError};
+/// use std::io::{self, ErrorKind};
+///
+/// let error = |input, kind| IoError {
+///     input,
+///     error: io::Error::from(kind),
+/// };
+///
+/// let not_found = error(Input::File("input.txt".into()), ErrorKind::NotFound);
+/// assert_eq!(not_found.kind(), ErrorKind::NotFound);
+/// assert_eq!(
+///     not_found.to_string(),
+///     "can't read file 'input.txt'; file does not exist"
+/// );
 ///
-/// Returns a [`String`] with the input collected from standard input or a file,
-/// as specified with command line arguments.
+/// let denied = error(Input::Directory("inputs".into()), ErrorKind::PermissionDenied);
+/// assert_eq!(denied.kind(), ErrorKind::PermissionDenied);
+/// assert_eq!(
+///     denied.to_string(),
+///     "can't read directory 'inputs'; check file permissions"
+/// );
 ///
-/// # Errors
+/// let other = error(Input::File("input.txt".into()), ErrorKind::Interrupted);
+/// assert_eq!(other.kind(), ErrorKind::Interrupted);
+/// assert_eq!(other.to_string(), "can't read file 'input.txt'");
 ///
-/// An error is returned if no arguments are passed,
-/// or if an error is encountered while reading input from stdin or a file.
-pub fn get(description: Description) -> Result<String, SomeError> {
-    let input = Input::from_args(env::args(), description)
-        .map_err(NoInput::display_help)?
-        .read_to_string()?;
-
-    Ok(input)
+/// let stdin = error(Input::Stdin, ErrorKind::NotFound);
+/// assert_eq!(stdin.to_string(), "can't read from stdin");
+/// ```
+///
+/// On Windows, a file is named without the verbatim prefix of a canonical path.
+/// ```
+/// # #[cfg(windows)]
+/// # {
+/// use input::{Input, IoError};
+/// use std::io::{self, ErrorKind};
+///
+/// let error = |file: &str| IoError {
+///     input: Input::File(file.into()),
+///     error: io::Error::from(ErrorKind::NotFound),
+/// };
+///
+/// assert_eq!(
+///     error(r"\\?\C:\aoc\input.txt").to_string(),
+///     r"can't read file 'C:\aoc\input.txt'; file does not exist"
+/// );
+/// assert_eq!(
+///     error(r"\\?\UNC\server\aoc\input.txt").to_string(),
+///     r"can't read file '\\server\aoc\input.txt'; file does not exist"
+/// );
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IoError {
+    pub input: Input,
+    pub error: io::Error,
 }
 
-/// Metadata of the app to be used when displaying help information.
-#[derive(Debug, Clone)]
-pub struct Description {
-    pub name: &'static str,
-    /// The name of the binary executing this code.
-    pub bin_name: Cow<'static, str>,
-    pub description: &'static str,
-    pub version: (u16, u16, u16),
+impl IoError {
+    /// Returns the kind of the wrapped [`io::Error`].
+    pub fn kind(&self) -> io::ErrorKind {
+        self.error.kind()
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self.kind() {
+            io::ErrorKind::NotFound => Some("file does not exist"),
+            io::ErrorKind::PermissionDenied => Some("check file permissions"),
+            io::ErrorKind::FileTooLarge => Some("input is too large"),
+            _ => None,
+        }
+    }
 }
 
-/// The location to search for input; either a named file or stdin.
-#[derive(Debug, Clone)]
-pub enum Input {
-    File(String),
-    Stdin,
+impl Error for IoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
 }
 
-impl Input {
-    /// Parse arguments for input source.
-    ///
-    /// # Errors
-    ///
-    /// If help information is requested, version information is requested,
-    /// or no arguments are passed at all, then [`NoInput`] is returned.
-    pub fn from_args(
-        mut args: impl Iterator<Item = String>,
-        mut description: Description,
-    ) -> Result<Self, NoInput> {
-        if let Some(bin_name) = args.next() {
-            description.bin_name = Cow::from(bin_name);
+impl Display for IoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.input {
+            Input::File(ref file) | Input::FileOrStdin(ref file) => {
+                write!(f, "can't read file '{}'", path::display(file))?;
+            }
+            Input::Directory(ref directory) => write!(f, "can't read directory '{directory}'")?,
+            Input::Glob(ref pattern) => return write!(f, "can't read files matching '{pattern}'"),
+            Input::Stdin => return write!(f, "can't read from stdin"),
+            Input::Memory(_) => return write!(f, "can't read in-memory input"),
+            Input::Fd(fd) => return write!(f, "can't read file descriptor {fd}"),
+            Input::Clipboard => return write!(f, "can't read the clipboard"),
+            Input::Aoc { year, day } => {
+                return write!(f, "can't get the input of day {day} of {year}")
+            }
         }
 
-        let file_is = |file, description| match file {
-            Some(file) => Ok(Self::File(file)),
-            None => Err(NoInput::NoArgs(description)),
-        };
-
-        let input = args.next();
-        match input.as_deref() {
-            Some("--help" | "-h") => Err(NoInput::Help(description)),
-            Some("--version" | "-V") => Err(NoInput::Version(description)),
-            Some("--stdin" | "-0") => Ok(Self::Stdin),
-            Some("--") => file_is(args.next(), description),
-            _ => file_is(input, description),
+        match self.hint() {
+            Some(hint) => write!(f, "; {hint}"),
+            None => Ok(()),
         }
     }
+}
 
-    /// Returns a [`String`] containing the input collected from standard input or a file.
-    ///
-    /// # Errors
-    ///
-    /// If an error is encountered while reading input from stdin or a file,
-    /// then [`io::Error`] is returned.
-    /// See [`fs::read_to_string`] and [`io::read_to_string`] for more information.
-    pub fn read_to_string(self) -> Result<String, IoError> {
-        match self {
-            Self::File(ref file) => fs::read_to_string(file),
-            Self::Stdin => io::read_to_string(io::stdin()),
-        }
-        .map_err(|error| IoError { input: self, error })
-    }
+/// An error returned when input is larger than the limit set with `--max-bytes`.
+#[derive(Debug, Clone, Copy)]
+pub struct InputTooLarge {
+    pub max_bytes: u64,
 }
 
-/// An error returned when no input source is specified.
-#[derive(Debug, Clone)]
-pub enum NoInput {
-    /// No valid arguments have been found.
-    NoArgs(Description),
-    /// Help text has been requested.
-    Help(Description),
-    /// Version information has been requested.
-    Version(Description),
+impl Error for InputTooLarge {}
+
+impl Display for InputTooLarge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let max_bytes = self.max_bytes;
+        write!(f, "input is larger than the limit of {max_bytes} bytes")
+    }
 }
 
-impl NoInput {
-    /// Return the app metadata, ignoring the error cause.
-    pub const fn description(&self) -> &Description {
-        match self {
-            Self::NoArgs(description) | Self::Help(description) | Self::Version(description) => {
-                description
-            }
-        }
+impl From<InputTooLarge> for io::Error {
+    fn from(error: InputTooLarge) -> Self {
+        Self::new(io::ErrorKind::FileTooLarge, error)
     }
+}
 
-    /// Display help or version information, then exit. Does nothing with [`Self::NoArgs`].
-    #[must_use]
-    pub fn display_help(self) -> Self {
-        if let Self::Help(_) | Self::Version(_) = self {
-            println!("{self}");
-            process::exit(0);
-        }
+/// An error marking the error it wraps as being caused by invalid input,
+/// so an app exits with code 3 because of it; see [`SomeError::exit_code`].
+///
+/// It's displayed as the wrapped error, and has the same source,
+/// so it doesn't show up among the sources of an error.
+/// ```
+/// use input::{InvalidInput, SomeError};
+/// use std::io;
+///
+/// let error = io::Error::other("line 4: expected 'A', 'B', or 'C', found 'Q'");
+/// let error = SomeError::new(InvalidInput::new(error));
+/// assert_eq!(error.to_string(), "line 4: expected 'A', 'B', or 'C', found 'Q'");
+/// assert_eq!(error.exit_code(), 3);
+/// ```
+#[derive(Debug)]
+pub struct InvalidInput(Box<dyn Error + Send + Sync + 'static>);
 
-        self
+impl InvalidInput {
+    pub fn new(error: impl Error + Send + Sync + 'static) -> Self {
+        Self(Box::new(error))
     }
 }
 
-impl Error for NoInput {}
+impl Error for InvalidInput {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
 
-impl Display for NoInput {
+impl Display for InvalidInput {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let Description {
-            name,
-            bin_name,
-            description,
-            version: (major, minor, patch),
-        } = self.description();
+        Display::fmt(&self.0, f)
+    }
+}
 
-        match self {
-            Self::NoArgs(_) => write!(
-                f,
-                "\
-The following required argument was not provided: <FILE>
+/// An error consisting of just a message.
+#[derive(Debug, Clone)]
+struct Message(&'static str);
 
-Usage: {bin_name} [OPTIONS] [FILE]
+impl Error for Message {}
 
-For more information try '--help'"
-            ),
-            Self::Help(_) => write!(
-                f,
-                "\
-{name} {major}.{minor}.{patch}
-Solution app for advent of code 2022.
-{description}
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
 
-Usage: {bin_name} [OPTIONS] [FILE]
+/// An error consisting of just a message built at runtime, created with [`SomeError::msg`].
+#[derive(Debug, Clone)]
+struct StringError(String);
 
-Args:
-    <FILE>    File to read as input
+impl Error for StringError {}
 
-Options:
-    -h, --help       Print help information
-    -V, --version    Print version information
-    -0  --stdin      Read input from stdin instead of a file"
-            ),
-            Self::Version(_) => write!(f, "{name} {major}.{minor}.{patch}"),
-        }
+impl Display for StringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
-/// An error wrapping [`io::Error`] with more context.
-#[derive(Debug)]
-pub struct IoError {
-    pub input: Input,
-    pub error: io::Error,
+/// An error created from the payload of a caught panic.
+#[derive(Debug, Clone)]
+pub struct Panic {
+    pub message: String,
 }
 
-impl Error for IoError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        Some(&self.error)
+impl Panic {
+    fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            (*message).to_owned()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic payload".to_owned()
+        };
+
+        Self { message }
     }
 }
 
-impl Display for IoError {
+impl Error for Panic {}
+
+impl Display for Panic {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self.input {
-            Input::File(ref file) => write!(f, "can't read file '{file}'"),
-            Input::Stdin => write!(f, "can't read from stdin"),
-        }
+        let message = &self.message;
+        write!(f, "solution panicked: {message}")
     }
 }
 
@@ -241,6 +3820,31 @@ impl Display for IoError {
 ///     )
 /// );
 /// ```
+///
+/// For a long chain, use the `+` flag instead, to number each source
+/// and indent it further than the one before, showing how deeply it's nested.
+/// ```
+/// use input::{Input, IoError, SomeError};
+/// use std::io::{self, ErrorKind};
+///
+/// let error = IoError {
+///     input: Input::Stdin,
+///     error: io::Error::new(ErrorKind::NotFound, "os error opening stdin"),
+/// };
+/// let error = SomeError::new(error)
+///     .context("can't parse strategy guide")
+///     .context("can't solve day 2");
+///
+/// assert_eq!(
+///     format!("{error:+}"),
+///     concat!(
+///         "error: can't solve day 2\n",
+///         "  1. can't parse strategy guide\n",
+///         "     2. can't read from stdin\n",
+///         "        3. os error opening stdin\n",
+///     )
+/// );
+/// ```
 #[derive(Debug)]
 pub struct SomeError(pub Box<dyn Error + Send + Sync + 'static>);
 
@@ -249,6 +3853,23 @@ impl SomeError {
         Self(Box::new(error))
     }
 
+    /// Create an error with just a message, and no source.
+    /// ```
+    /// use input::SomeError;
+    ///
+    /// fn find_badge(rucksacks: &[&str]) -> Result<char, SomeError> {
+    ///     let group = rucksacks.len();
+    ///     Err(SomeError::msg(format!("no badge is shared by the group of {group}")))
+    /// }
+    ///
+    /// let error = find_badge(&["vJrw", "jqHR"]).unwrap_err();
+    /// assert_eq!(error.to_string(), "no badge is shared by the group of 2");
+    /// assert!(error.source().is_none());
+    /// ```
+    pub fn msg(message: impl Into<String>) -> Self {
+        Self::new(StringError(message.into()))
+    }
+
     /// Iterate over this error and all of its sources.
     pub const fn iter(&self) -> ErrorChain<'_> {
         ErrorChain::new(&*self.0)
@@ -257,6 +3878,146 @@ impl SomeError {
     pub fn source(&self) -> Option<&(dyn Error + 'static)> {
         self.0.deref().source()
     }
+
+    /// Wrap this error in a message describing what was being done when it occurred.
+    ///
+    /// The message is displayed first, followed by this error and all of its sources.
+    /// ```
+    /// use input::{Input, IoError, SomeError};
+    /// use std::io::{self, ErrorKind};
+    ///
+    /// let error = SomeError::new(IoError {
+    ///     input: Input::Stdin,
+    ///     error: io::Error::new(ErrorKind::Other, "os error reading stdin"),
+    /// });
+    /// let error = error.context("while loading day 7 input");
+    ///
+    /// assert_eq!(
+    ///     format!("{error:#}"),
+    ///     concat!(
+    ///         "error: while loading day 7 input\n",
+    ///         "  - can't read from stdin\n",
+    ///         "  - os error reading stdin\n"
+    ///     )
+    /// );
+    /// ```
+    #[must_use]
+    pub fn context(self, message: impl Display) -> Self {
+        Self::new(Context {
+            message: message.to_string(),
+            error: self,
+        })
+    }
+
+    /// Returns the code an app exits with because of this error.
+    ///
+    /// | Code | Meaning |
+    /// |------|---------|
+    /// | 0    | Success, or help or version information was displayed, or a broken pipe |
+    /// | 1    | Any other error, such as an invalid argument or a failed solution |
+    /// | 2    | Input couldn't be read |
+    /// | 3    | Input was read, but is invalid |
+    ///
+    /// Without an error, an app can choose any code by returning an [`Outcome::Exit`].
+    ///
+    /// Input is invalid if it isn't utf-8, or if this error or any of its sources
+    /// is an [`InvalidInput`] or an error parsing a number, `bool`, or `char` from a string.
+    /// Errors parsing input should wrap such an error as their source to be detected,
+    /// as with [`ParseIntError`], or be wrapped in an [`InvalidInput`].
+    /// Errors parsing the value of a flag are [`FlagError`]s, which are invalid arguments.
+    /// ```
+    /// use input::{FlagError, Input, IoError, SomeError};
+    /// use std::io::{self, ErrorKind};
+    ///
+    /// let error = SomeError::new(IoError {
+    ///     input: Input::File("input.txt".to_owned()),
+    ///     error: io::Error::new(ErrorKind::NotFound, "no such file"),
+    /// });
+    /// assert_eq!(error.exit_code(), 2);
+    ///
+    /// let error = SomeError::new("x".parse::<u64>().unwrap_err()).context("line 1");
+    /// assert_eq!(error.exit_code(), 3);
+    ///
+    /// let error = SomeError::new(FlagError {
+    ///     flag: "kth",
+    ///     value: "x".to_owned(),
+    ///     error: Box::new("x".parse::<u64>().unwrap_err()),
+    /// });
+    /// assert_eq!(error.exit_code(), 1);
+    /// ```
+    ///
+    /// Output piped into a program that exits early, like `head`, fails with a broken pipe;
+    /// that's conventionally not an error, so the app exits successfully without displaying it.
+    /// ```
+    /// use input::SomeError;
+    /// use std::io::{self, ErrorKind};
+    ///
+    /// let error = SomeError::new(io::Error::from(ErrorKind::BrokenPipe));
+    /// assert!(error.is_broken_pipe());
+    /// assert_eq!(error.exit_code(), 0);
+    /// ```
+    pub fn exit_code(&self) -> u8 {
+        if self.is_broken_pipe() {
+            return 0;
+        }
+
+        // a flag's value is parsed like input, but is an invalid argument
+        if self.iter().any(|error| error.is::<FlagError>()) {
+            return 1;
+        }
+
+        let invalid = self.iter().any(|error| {
+            error.is::<InvalidInput>()
+                || error.is::<ParseIntError>()
+                || error.is::<ParseFloatError>()
+                || error.is::<ParseBoolError>()
+                || error.is::<ParseCharError>()
+                || error
+                    .downcast_ref::<io::Error>()
+                    .is_some_and(|error| error.kind() == io::ErrorKind::InvalidData)
+        });
+
+        if invalid {
+            3
+        } else if self.iter().any(|error| error.is::<IoError>()) {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Returns `true` if output couldn't be written because the pipe it was written to closed.
+    ///
+    /// An app writing its own output with [`writeln!`] and `?`, rather than [`println!`],
+    /// which panics on a broken pipe, gets the same quiet exit as output printed by this crate.
+    /// A broken pipe while reading input is reported as an [`IoError`], so it's still an error.
+    pub fn is_broken_pipe(&self) -> bool {
+        let broken_pipe = self.iter().any(|error| {
+            error
+                .downcast_ref::<io::Error>()
+                .is_some_and(|error| error.kind() == io::ErrorKind::BrokenPipe)
+        });
+        broken_pipe && !self.iter().any(|error| error.is::<IoError>())
+    }
+}
+
+/// An error wrapped in a message, created with [`SomeError::context`].
+#[derive(Debug)]
+struct Context {
+    message: String,
+    error: SomeError,
+}
+
+impl Error for Context {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error.0)
+    }
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
 impl Display for SomeError {
@@ -269,6 +4030,14 @@ impl Display for SomeError {
                 writeln!(f, "  - {error}")?;
             }
 
+            Ok(())
+        } else if f.sign_plus() {
+            writeln!(f, "error: {error}")?;
+            for (depth, error) in self.iter().enumerate().skip(1) {
+                let indent = 2 + 3 * (depth - 1);
+                writeln!(f, "{:indent$}{depth}. {error}", "")?;
+            }
+
             Ok(())
         } else {
             Display::fmt(error, f)
@@ -312,3 +4081,132 @@ impl<'a> Iterator for ErrorChain<'a> {
 }
 
 impl<'a> FusedIterator for ErrorChain<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter;
+
+    fn description() -> Description {
+        Description {
+            name: "app",
+            bin_name: "app".into(),
+            description: "",
+            version: (0, 0, 0),
+            flags: &[],
+            standalone_flags: &[],
+            env_vars: &[],
+            examples: &[],
+        }
+    }
+
+    fn get(args: &[&str]) -> Result<String, SomeError> {
+        let args = iter::once("app").chain(args.iter().copied());
+        get_with_args(description(), args.map(str::to_owned))
+    }
+
+    /// Create an empty directory for a test, named after it.
+    fn temp_dir(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("input-test-{name}"));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn lossy_files_count_bytes_read_against_the_limit() {
+        let directory = temp_dir("lossy-limit");
+        fs::write(directory.join("a.txt"), b"\xff\xff\xff\xff\n").unwrap();
+        fs::write(directory.join("b.txt"), b"1\n").unwrap();
+        let path = directory.to_str().unwrap();
+
+        // 7 bytes are read, though replacing the invalid bytes makes 15 bytes of text
+        let input = get(&["--lossy", "--max-bytes", "7", path]).unwrap();
+        assert_eq!(input, "\u{fffd}\u{fffd}\u{fffd}\u{fffd}\n1\n");
+
+        let error = get(&["--lossy", "--max-bytes", "6", path]).unwrap_err();
+        let error = error.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert!(error.contains(&InputTooLarge { max_bytes: 6 }.to_string()));
+
+        let pattern = format!("{path}/*.txt");
+        let input = get(&["--lossy", "--max-bytes", "10", "--input-glob", &pattern]).unwrap();
+        assert_eq!(input.lines().count(), 2);
+    }
+
+    #[test]
+    fn argument_errors_are_displayed_in_the_requested_format() {
+        let run = |args: &[&str]| {
+            let args = iter::once("app").chain(args.iter().copied());
+            let result = try_run(description(), args.map(str::to_owned), |_| {
+                Ok(Outcome::Success)
+            });
+            result.err().map(|(_, format)| format)
+        };
+
+        let format = run(&["--error-format", "json", "--unknown", "--stdin"]);
+        assert_eq!(format, Some(ErrorFormat::Json));
+
+        let format = run(&["--unknown", "--error-format", "json", "--stdin"]);
+        assert_eq!(format, Some(ErrorFormat::Json));
+
+        let format = run(&["--error-format", "yaml", "--stdin"]);
+        assert_eq!(format, Some(ErrorFormat::Text));
+    }
+
+    #[test]
+    fn split_is_rejected_when_getting_input() {
+        let error = get(&["--split", "---", "--stdin"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'--split' isn't supported by this app, as it solves all of its input at once"
+        );
+    }
+
+    #[test]
+    fn exit_codes_depend_on_the_kind_of_error() {
+        let directory = temp_dir("exit-codes");
+        let path = directory.join("input.txt");
+        let file = path.to_str().unwrap();
+
+        let error = get(&[file]).unwrap_err();
+        assert_eq!(error.exit_code(), 2, "missing file");
+
+        fs::write(&path, b"\xff\n").unwrap();
+        let error = get(&[file]).unwrap_err();
+        assert_eq!(error.exit_code(), 3, "invalid utf-8");
+
+        let error = SomeError::new(FlagError {
+            flag: "kth",
+            value: "abc".to_owned(),
+            error: Box::new("abc".parse::<u64>().unwrap_err()),
+        });
+        assert_eq!(error.exit_code(), 1, "invalid flag value");
+
+        let error = SomeError::new(InvalidInput::new(Message("expected 'A'")));
+        assert_eq!(
+            error.context("while scoring").exit_code(),
+            3,
+            "invalid input"
+        );
+
+        assert_eq!(SomeError::msg("no solution").exit_code(), 1, "other error");
+    }
+
+    #[test]
+    fn help_lists_the_environment_variables_read() {
+        let description = Description {
+            env_vars: &[("APP_VAR", "Read by the app")],
+            ..description()
+        };
+        let help = NoInput::Help(Box::new(description)).to_string();
+        let (_, env_vars) = help.split_once("Environment:").unwrap();
+
+        let position = |name| env_vars.find(name).unwrap();
+        assert!(position("NO_COLOR") < position("APP_VAR"));
+        assert!(env_vars.contains("COLUMNS"));
+        assert_eq!(env_vars.contains("AOC_SESSION"), cfg!(feature = "aoc"));
+
+        let help = NoInput::Help(Box::new(self::description())).to_string();
+        assert!(help.contains("Environment:"));
+    }
+}
@@ -0,0 +1,104 @@
+//! Memory mapped input, available with the `memmap` feature.
+
+use std::fmt::{self, Debug, Formatter};
+use std::fs;
+use std::io;
+use std::ops::Deref;
+
+use crate::{Input, IoError};
+
+impl Input {
+    /// Map a file of input into memory, to parse it without copying it into a [`String`].
+    ///
+    /// The file is checked to be valid utf-8 once, when it's mapped.
+    /// Any other input, like stdin or a directory, is read as with [`Self::read_to_string`].
+    /// ```
+    /// use input::Input;
+    /// # use std::{env, fs};
+    ///
+    /// let file = env::temp_dir().join("input-mmap.txt");
+    /// fs::write(&file, "1000\n2000\n").unwrap();
+    ///
+    /// // SAFETY: nothing else writes to the file while it's mapped
+    /// let input = unsafe { Input::File(file.to_string_lossy().into_owned()).mmap() };
+    /// assert_eq!(input.unwrap().lines().count(), 2);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The mapped file must not be modified or truncated while the [`MappedInput`] is alive,
+    /// by this or any other process.
+    /// The operating system doesn't prevent it, and if it happens, the text can change
+    /// after it was checked to be utf-8, which is undefined behavior,
+    /// or reading it can fault, as with `SIGBUS` on Unix.
+    /// Only map input that nothing else is writing to.
+    ///
+    /// # Errors
+    ///
+    /// If the input can't be read or mapped, or isn't valid utf-8, then [`IoError`] is returned.
+    pub unsafe fn mmap(self) -> Result<MappedInput, IoError> {
+        let input = self.resolve()?;
+        let Self::File(ref path) = input else {
+            return input
+                .read_to_string()
+                .map(|input| MappedInput(Inner::Read(input)));
+        };
+
+        let map = fs::File::open(crate::path::long(path)).and_then(|file| {
+            // SAFETY: the caller guarantees that the file doesn't change while it's mapped
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            match std::str::from_utf8(&map) {
+                Ok(_) => Ok(map),
+                Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+            }
+        });
+
+        match map {
+            Ok(map) => Ok(MappedInput(Inner::Mapped(map))),
+            Err(error) => Err(IoError { input, error }),
+        }
+    }
+}
+
+/// Input mapped into memory with [`Input::mmap`], which derefs to its text.
+pub struct MappedInput(Inner);
+
+enum Inner {
+    /// A mapped file, which has been checked to be utf-8.
+    Mapped(memmap2::Mmap),
+    /// Input that can't be mapped, read as usual.
+    Read(String),
+}
+
+impl MappedInput {
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // SAFETY: the map was checked to be utf-8 when it was created,
+            // and the caller of `Input::mmap` guarantees the file doesn't change while mapped
+            Inner::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+            Inner::Read(input) => input,
+        }
+    }
+
+    /// Returns `true` if the input is mapped, rather than read into memory.
+    pub const fn is_mapped(&self) -> bool {
+        matches!(self.0, Inner::Mapped(_))
+    }
+}
+
+impl Deref for MappedInput {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Debug for MappedInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedInput")
+            .field("mapped", &self.is_mapped())
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,50 @@
+//! Paths of input files as shown to the user and as given to the operating system.
+//!
+//! Both are the path as given on Unix; only Windows has verbatim paths and a path length limit.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Returns the path to show in messages, without the verbatim prefix `\\?\` on Windows.
+///
+/// A canonical path is verbatim on Windows, which is harder to read and rarely typed by hand.
+#[cfg(windows)]
+pub(crate) fn display(path: &str) -> Cow<'_, str> {
+    match path.strip_prefix(r"\\?\UNC\") {
+        Some(share) => Cow::Owned(format!(r"\\{share}")),
+        None => Cow::Borrowed(path.strip_prefix(r"\\?\").unwrap_or(path)),
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn display(path: &str) -> Cow<'_, str> {
+    Cow::Borrowed(path)
+}
+
+/// Returns the path to open, in verbatim form on Windows if it's too long to open otherwise.
+///
+/// Without the verbatim prefix, Windows limits a path to 260 characters.
+/// A verbatim path isn't normalized when opened, so it's made absolute with backslashes first.
+#[cfg(windows)]
+pub(crate) fn long(path: &str) -> Cow<'_, Path> {
+    const MAX_PATH: usize = 260;
+
+    if path.len() < MAX_PATH || path.starts_with(r"\\?\") {
+        return Cow::Borrowed(Path::new(path));
+    }
+
+    let Ok(absolute) = std::path::absolute(path) else {
+        return Cow::Borrowed(Path::new(path));
+    };
+    let absolute = absolute.to_string_lossy().replace('/', r"\");
+    let verbatim = match absolute.strip_prefix(r"\\") {
+        Some(share) => format!(r"\\?\UNC\{share}"),
+        None => format!(r"\\?\{absolute}"),
+    };
+    Cow::Owned(verbatim.into())
+}
+
+#[cfg(not(windows))]
+pub(crate) fn long(path: &str) -> Cow<'_, Path> {
+    Cow::Borrowed(Path::new(path))
+}
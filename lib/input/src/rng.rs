@@ -0,0 +1,32 @@
+//! A small random number generator, for apps that choose or generate input at random.
+
+/// The splitmix64 generator; small and fast, with good enough randomness
+/// for sampling and generating test input, but not for cryptography.
+///
+/// The same seed always generates the same numbers.
+/// ```
+/// use input::SplitMix64;
+///
+/// let mut rng = SplitMix64::new(7);
+/// let rolls: Vec<u64> = (0..3).map(|_| rng.next_u64() % 6 + 1).collect();
+///
+/// let mut again = SplitMix64::new(7);
+/// assert_eq!(rolls, (0..3).map(|_| again.next_u64() % 6 + 1).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns the next number, advancing the generator.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
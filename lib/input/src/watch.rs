@@ -0,0 +1,80 @@
+//! Running again whenever the input file changes, available with the `watch` feature.
+
+use std::io;
+use std::path::Path;
+
+/// Call `run` once, then again every time the file is modified, until Ctrl-C is pressed.
+///
+/// # Errors
+///
+/// If the file can't be watched, an error is returned.
+/// Without the `watch` feature, an error of kind [`io::ErrorKind::Unsupported`] is returned.
+#[cfg(feature = "watch")]
+pub(crate) fn watch(file: &Path, mut run: impl FnMut()) -> io::Result<()> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    enum Event {
+        Changed,
+        Stop,
+    }
+
+    let (sender, events) = mpsc::channel();
+
+    let stop = sender.clone();
+    ctrlc::set_handler(move || {
+        let _ = stop.send(Event::Stop);
+    })
+    .map_err(io::Error::other)?;
+
+    // editors often save by replacing the file, which a watch on the file itself would miss,
+    // so watch its directory for events naming the file
+    let name = file.file_name().map(ToOwned::to_owned);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        let changed = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+        if changed
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == name.as_deref())
+        {
+            let _ = sender.send(Event::Changed);
+        }
+    })
+    .map_err(io::Error::other)?;
+
+    let directory = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    watcher
+        .watch(directory, RecursiveMode::NonRecursive)
+        .map_err(io::Error::other)?;
+
+    run();
+    while let Ok(Event::Changed) = events.recv() {
+        // a single save is often several events; wait for them to settle, then run once
+        loop {
+            match events.recv_timeout(Duration::from_millis(50)) {
+                Ok(Event::Changed) => continue,
+                Ok(Event::Stop) => return Ok(()),
+                Err(_) => break,
+            }
+        }
+
+        println!("---");
+        run();
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "watch"))]
+pub(crate) fn watch(_file: &Path, _run: impl FnMut()) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "watching input requires the 'watch' feature",
+    ))
+}
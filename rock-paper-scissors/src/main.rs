@@ -1,20 +1,102 @@
 use std::{
     error::Error,
     fmt::{self, Display, Formatter},
+    iter,
     str::FromStr,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use input::Description;
+use input::{
+    Answers, Description, Flag, FlagError, Flags, InputFile, InvalidInput, SomeError, SplitMix64,
+};
 
 fn main() {
-    input::with(
+    input::with_each_file(
         Description {
-            name: "rock-paper-scissors",
-            bin_name: "rock-paper-scissors".into(),
-            description: "\
+            flags: &[
+                Flag {
+                    name: "csv",
+                    value: None,
+                    help: "Print each round as csv instead of the sums",
+                },
+                Flag {
+                    name: "wide",
+                    value: None,
+                    help: "Sum scores into 128 bit integers, for inputs too large for 64 bits",
+                },
+                Flag {
+                    name: "separator",
+                    value: Some("CHAR"),
+                    help: "Require CHAR between the values of a row instead of any whitespace",
+                },
+                Flag {
+                    name: "limit",
+                    value: Some("N"),
+                    help: "Only parse the first N rows; errors in later rows aren't reported",
+                },
+                Flag {
+                    name: "tournament",
+                    value: None,
+                    help: "Score each file of input as a separate player, then rank the players",
+                },
+                Flag {
+                    name: "explain",
+                    value: None,
+                    help: "Print how each round is scored using the first values",
+                },
+                Flag {
+                    name: "streaks",
+                    value: None,
+                    help: "Print the most rounds lost and won in a row using the first values",
+                },
+                Flag {
+                    name: "stats",
+                    value: None,
+                    help: "Print the best score of a single round along with each total",
+                },
+                Flag {
+                    name: "threads",
+                    value: Some("N"),
+                    help: "Score on N threads; only faster for millions of rounds",
+                },
+                Flag {
+                    name: "weight-linear",
+                    value: None,
+                    help:
+                        "Multiply the score of each round by its number, so later rounds count more",
+                },
+                Flag {
+                    name: "blocks",
+                    value: None,
+                    help: "Score each block of rows between empty lines, then all blocks together",
+                },
+                Flag {
+                    name: "self-check",
+                    value: None,
+                    help: "Check that the rows parse into as many rounds with either values",
+                },
+                Flag {
+                    name: "seed",
+                    value: Some("SEED"),
+                    help: "Generate rows reproducibly from the integer SEED",
+                },
+            ],
+            standalone_flags: &[Flag {
+                name: "generate",
+                value: Some("N"),
+                help: "Print N random valid rows instead of scoring; see '--seed'",
+            }],
+            examples: &[
+                "rock-paper-scissors input.txt",
+                "cat input.txt | rock-paper-scissors --stdin",
+                "rock-paper-scissors --tournament players/",
+            ],
+            ..input::description!(
+                "\
 Takes a newline separated list,
 where each row starts with 'A', 'B', or 'C',
-then a space, then 'X', 'Y', or 'Z'. 
+then a whitespace character, then 'X', 'Y', or 'Z'.
 Each row is assigned a score based on the following lookup table.
 Returns the sum of scores using the first values,
 then the sum of scores using the second values.
@@ -28,26 +110,372 @@ then the sum of scores using the second values.
 'C X' => 7 | 2
 'C Y' => 2 | 6
 'C Z' => 6 | 7
-            ",
-            version: (0, 1, 0),
+            "
+            )
         },
-        |input| {
-            let matches_score = input.parse::<Matches<Match>>()?.score();
-            let strategic_score = input.parse::<Matches<Strategy>>()?.score();
+        |files, flags| {
+            let options = ParseOptions::from_flags(flags)?;
+
+            if flags.is_set("tournament") {
+                for file in files {
+                    Matches::<Match>::parse(&file.contents, options)
+                        .map_err(|error| PlayerError::new(file, InvalidInput::new(error)))?;
+                }
+                return Ok(());
+            }
 
-            println!("{matches_score}");
-            println!("{strategic_score}");
+            let input = concat(files);
+            if flags.is_set("blocks") {
+                Matches::<Match>::score_by_block(&input, options).map_err(InvalidInput::new)?;
+                Matches::<Strategy>::score_by_block(&input, options).map_err(InvalidInput::new)?;
+                return Ok(());
+            }
+
+            Matches::<Match>::parse(&input, options).map_err(InvalidInput::new)?;
+            Matches::<Strategy>::parse(&input, options).map_err(InvalidInput::new)?;
 
             Ok(())
         },
+        |files, flags| {
+            if let Some(rows) = flags.parse("generate")? {
+                return Ok(Answers::other(generate(rows, flags.parse("seed")?)));
+            }
+
+            let options = ParseOptions::from_flags(flags)?;
+
+            if flags.is_set("tournament") {
+                return Ok(Answers::other(leaderboard(files, options)?));
+            }
+
+            solve(&concat(files), options, flags)
+        },
     );
 }
 
+/// Generate random valid rows, which are the same for the same seed.
+///
+/// Without a seed, one is taken from the current time and printed to stderr,
+/// so the rows can be generated again.
+fn generate(rows: usize, seed: Option<u64>) -> String {
+    let seed = seed.unwrap_or_else(|| {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH);
+        let seed = now.map_or(0, |now| now.as_nanos() as u64);
+        input::note(format_args!("generating with seed {seed}"));
+        seed
+    });
+
+    let mut rng = SplitMix64::new(seed);
+    let rows = (0..rows).map(|_| {
+        let left = ['A', 'B', 'C'][(rng.next_u64() % 3) as usize];
+        let right = ['X', 'Y', 'Z'][(rng.next_u64() % 3) as usize];
+        format!("{left} {right}")
+    });
+    rows.collect::<Vec<_>>().join("\n")
+}
+
+/// Concatenate the files of input, which are scored together outside of a tournament.
+fn concat(files: &[InputFile]) -> String {
+    files.iter().map(|file| file.contents.as_str()).collect()
+}
+
+fn solve(input: &str, options: ParseOptions, flags: &Flags) -> Result<Answers, SomeError> {
+    if flags.is_set("self-check") {
+        return Ok(Answers::other(self_check(input, options)?));
+    }
+
+    if flags.is_set("blocks") {
+        let (matches_blocks, matches_score) =
+            Matches::<Match>::score_by_block(input, options).map_err(InvalidInput::new)?;
+        let (strategic_blocks, strategic_score) =
+            Matches::<Strategy>::score_by_block(input, options).map_err(InvalidInput::new)?;
+        let blocks = iter::zip(matches_blocks, strategic_blocks).enumerate().map(
+            |(block, (matches, strategies))| {
+                format!("block {}: {matches} | {strategies}", block + 1)
+            },
+        );
+        let total = format!("total: {matches_score} | {strategic_score}");
+        return Ok(Answers::other(
+            blocks.chain([total]).collect::<Vec<_>>().join("\n"),
+        ));
+    }
+
+    let matches = Matches::<Match>::parse(input, options).map_err(InvalidInput::new)?;
+    if let Some(limit) = options.limit {
+        let rows = matches.len();
+        input::note(format_args!("parsed {rows} rows, with a limit of {limit}"));
+    }
+
+    if flags.is_set("csv") {
+        return Ok(Answers::other(matches.csv()));
+    }
+
+    if flags.is_set("explain") {
+        return Ok(Answers::other(matches.explain()));
+    }
+
+    if flags.is_set("streaks") {
+        let losses = matches.longest_loss_streak();
+        let wins = matches.longest_win_streak();
+        return Ok(Answers::other(format!(
+            "longest loss streak: {losses}\nlongest win streak: {wins}"
+        )));
+    }
+
+    let strategies = Matches::<Strategy>::parse(input, options).map_err(InvalidInput::new)?;
+
+    if flags.is_set("stats") {
+        let (matches_score, matches_best) = matches.score_and_max()?;
+        let (strategic_score, strategic_best) = strategies.score_and_max()?;
+        return Ok(Answers::parts(
+            format!(
+                "{}: total {matches_score}, best round {matches_best}",
+                matches.label()
+            ),
+            format!(
+                "{}: total {strategic_score}, best round {strategic_best}",
+                strategies.label()
+            ),
+        ));
+    }
+
+    if flags.is_set("weight-linear") {
+        let linear = |round| round as u64 + 1;
+        let matches_score = matches.weighted_score(linear)?;
+        let strategic_score = strategies.weighted_score(linear)?;
+        return Ok(Answers::parts(matches_score, strategic_score));
+    }
+
+    if flags.is_set("wide") {
+        let matches_score = matches.total::<u128>()?;
+        let strategic_score = strategies.total::<u128>()?;
+        return Ok(Answers::parts(matches_score, strategic_score));
+    }
+
+    if let Some(threads) = flags.parse("threads")? {
+        let matches_score = matches.par_score(threads)?;
+        let strategic_score = strategies.par_score(threads)?;
+        return Ok(Answers::parts(matches_score, strategic_score));
+    }
+
+    let matches_score = matches.score()?;
+    let strategic_score = strategies.score()?;
+
+    Ok(Answers::parts(matches_score, strategic_score))
+}
+
+/// Interpret the same parsed rows both ways, failing if they don't make as many rounds,
+/// which would mean one interpretation lost or gained rows.
+fn self_check(input: &str, options: ParseOptions) -> Result<String, SomeError> {
+    let rows = Row::parse_all(input, options).collect::<Result<Vec<_>, _>>();
+    let rows = rows.map_err(InvalidInput::new)?;
+    let matches = Matches::<Match>::from_rows(rows.iter().copied());
+    let strategies = Matches::<Strategy>::from_rows(rows.iter().copied());
+
+    if matches.len() != strategies.len() {
+        return Err(SomeError::msg(format!(
+            "{} rows parsed into {} {} rounds, but {} {} rounds",
+            rows.len(),
+            matches.len(),
+            matches.label(),
+            strategies.len(),
+            strategies.label()
+        )));
+    }
+
+    Ok(format!(
+        "{} rows parsed into as many {} and {} rounds",
+        rows.len(),
+        matches.label(),
+        strategies.label()
+    ))
+}
+
+/// Rank each file by the score of its matches, highest first, with ties in order of name.
+fn leaderboard(files: &[InputFile], options: ParseOptions) -> Result<String, PlayerError> {
+    let mut players = files
+        .iter()
+        .map(|file| {
+            let matches = Matches::<Match>::parse(&file.contents, options)
+                .map_err(|error| PlayerError::new(file, InvalidInput::new(error)))?;
+            let score = matches
+                .score()
+                .map_err(|error| PlayerError::new(file, error))?;
+            Ok((file.name(), score))
+        })
+        .collect::<Result<Vec<_>, PlayerError>>()?;
+
+    players.sort_unstable_by(|(name, score), (other_name, other_score)| {
+        other_score.cmp(score).then_with(|| name.cmp(other_name))
+    });
+
+    let rows = players
+        .iter()
+        .enumerate()
+        .map(|(rank, (name, score))| format!("{}. {name}: {score}", rank + 1));
+    Ok(rows.collect::<Vec<_>>().join("\n"))
+}
+
 struct Matches<T>(Vec<T>);
 
-impl<T: Score> Score for Matches<T> {
-    fn score(&self) -> u64 {
-        self.0.iter().map(T::score).sum()
+impl<T> Matches<T> {
+    /// The number of rounds parsed, which is fewer than the input's rows with a limit.
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T: Score> Matches<T> {
+    /// The label of the scored rounds, even if there are none.
+    const fn label(&self) -> &'static str {
+        T::LABEL
+    }
+
+    fn score(&self) -> Result<u64, Overflow> {
+        self.total()
+    }
+
+    /// Returns the total score and the best score of a single round, in one pass.
+    ///
+    /// Without any rounds, the best score is 0.
+    fn score_and_max(&self) -> Result<(u64, u64), Overflow> {
+        self.0
+            .iter()
+            .enumerate()
+            .try_fold((0, 0), |(total, max), (round, item)| {
+                let overflow = || Overflow {
+                    label: item.label(),
+                    round: round + 1,
+                };
+                let score = item.score_checked().ok_or_else(overflow)?;
+                let total = total.checked_add_score(score).ok_or_else(overflow)?;
+                Ok((total, max.max(score)))
+            })
+    }
+
+    /// Sum the score of every round like [`Self::score`], split into chunks scored on `threads`
+    /// threads at once.
+    ///
+    /// Starting threads costs more than scoring thousands of rounds,
+    /// so this is only faster for millions of rounds; otherwise prefer [`Self::score`].
+    /// The result is always the same as that of [`Self::score`], including on overflow,
+    /// when the rounds are scored again in order to find the round that overflowed.
+    fn par_score(&self, threads: usize) -> Result<u64, Overflow>
+    where
+        T: Sync,
+    {
+        let chunk = self.0.len().div_ceil(threads.max(1)).max(1);
+        let sum = |rounds: &[T]| {
+            rounds.iter().try_fold(0, |total: u64, round| {
+                round
+                    .score_checked()
+                    .and_then(|score| total.checked_add(score))
+            })
+        };
+
+        let total = thread::scope(|scope| {
+            let partials: Vec<_> = self
+                .0
+                .chunks(chunk)
+                .map(|rounds| scope.spawn(move || sum(rounds)))
+                .collect();
+            partials
+                .into_iter()
+                .map(|partial| {
+                    partial
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .try_fold(0, |total: u64, partial| total.checked_add(partial?))
+        });
+
+        match total {
+            Some(total) => Ok(total),
+            None => self.score(),
+        }
+    }
+
+    /// Sum the score of every round multiplied by a weight from its index, starting at 0.
+    ///
+    /// Fails rather than wrapping if a weighted score or the sum overflows.
+    fn weighted_score(&self, weight: impl Fn(usize) -> u64) -> Result<u64, Overflow> {
+        self.0
+            .iter()
+            .enumerate()
+            .try_fold(0, |total: u64, (round, item)| {
+                item.score_checked()
+                    .and_then(|score| score.checked_mul(weight(round)))
+                    .and_then(|score| total.checked_add(score))
+                    .ok_or(Overflow {
+                        label: item.label(),
+                        round: round + 1,
+                    })
+            })
+    }
+
+    /// Sum the score of every round into `N`, failing rather than wrapping on overflow.
+    fn total<N: Total>(&self) -> Result<N, Overflow> {
+        self.0
+            .iter()
+            .enumerate()
+            .try_fold(N::ZERO, |total, (round, item)| {
+                let score = item.score_checked();
+                let total = score.and_then(|score| total.checked_add_score(score));
+                total.ok_or(Overflow {
+                    label: item.label(),
+                    round: round + 1,
+                })
+            })
+    }
+}
+
+impl Matches<Match> {
+    fn csv(&self) -> String {
+        let rows = self.0.iter().enumerate().map(|(line, round)| {
+            let Match { you, opponent } = round;
+            let outcome = you.match_with(*opponent);
+            let score = round.score();
+            format!("{},{opponent},{you},{outcome},{score}", line + 1)
+        });
+
+        iter::once("line,opponent,you,outcome,score".to_owned())
+            .chain(rows)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the most rounds lost in a row, or 0 without any rounds.
+    fn longest_loss_streak(&self) -> usize {
+        self.longest_streak(Outcome::Loss)
+    }
+
+    /// Returns the most rounds won in a row, or 0 without any rounds.
+    fn longest_win_streak(&self) -> usize {
+        self.longest_streak(Outcome::Win)
+    }
+
+    fn longest_streak(&self, outcome: Outcome) -> usize {
+        let (_, longest) = self.0.iter().fold((0, 0), |(streak, longest), round| {
+            if round.you.match_with(round.opponent) == outcome {
+                (streak + 1, longest.max(streak + 1))
+            } else {
+                (0, longest)
+            }
+        });
+        longest
+    }
+
+    /// Describe how each round is scored, one round per line.
+    fn explain(&self) -> String {
+        let rows = self.0.iter().map(|round| {
+            let Match { you, opponent } = round;
+            let (outcome, outcome_score, hand, hand_score) = round.score_breakdown();
+            let score = outcome_score + hand_score;
+            format!(
+                "You played {you} vs {opponent} \u{2192} \
+                {outcome} (+{outcome_score}) + {hand} (+{hand_score}) = {score}"
+            )
+        });
+        rows.collect::<Vec<_>>().join("\n")
     }
 }
 
@@ -55,92 +483,230 @@ impl<T: From<Row>> FromStr for Matches<T> {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        input
-            .lines()
-            .map(Row::from_str)
+        Self::parse(input, ParseOptions::default())
+    }
+}
+
+impl<T: From<Row>> Matches<T> {
+    /// Parse each row of the input into a round, as with [`Row::parse_all`].
+    fn parse(input: &str, options: ParseOptions) -> Result<Self, ParseError> {
+        Row::parse_all(input, options)
             .map(|row| row.map(Row::into))
             .collect::<Result<Vec<T>, ParseError>>()
             .map(Self)
     }
+
+    /// Score each block of rows separated by empty lines,
+    /// returning the score of every block along with the total score of all of them.
+    ///
+    /// Each empty line ends a block, so consecutive empty lines make blocks without rows,
+    /// which score 0, but empty lines at the end of input don't make any more blocks.
+    /// Otherwise rows are parsed as with [`Row::parse_all`], though `limit` only counts rows,
+    /// not the empty lines between blocks.
+    fn score_by_block(input: &str, options: ParseOptions) -> Result<(Vec<u64>, u64), ParseError>
+    where
+        T: Score,
+    {
+        let ParseOptions { separator, limit } = options;
+        let lines: Vec<&str> = input.lines().collect();
+        let end = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| !row.is_empty())
+            .take(limit.unwrap_or(usize::MAX))
+            .last()
+            .map_or(0, |(last, _)| last + 1);
+
+        let mut rounds = Vec::new();
+        let mut blocks = Vec::new();
+        if end > 0 {
+            let mut line = 1;
+            for block in lines[..end].split(|row| row.is_empty()) {
+                for row in block {
+                    let row =
+                        Row::parse(row, separator).map_err(|error| ParseError { line, ..error })?;
+                    rounds.push(T::from(row));
+                    line += 1;
+                }
+                blocks.push(block.len());
+                line += 1;
+            }
+        }
+
+        // a round scores at most 9, and input is held in memory, so there are far fewer than
+        // `u64::MAX / 9` rounds and neither a block nor the total can overflow
+        let mut rounds = rounds.as_slice();
+        let blocks: Vec<u64> = blocks
+            .into_iter()
+            .map(|len| {
+                let (block, rest) = rounds.split_at(len);
+                rounds = rest;
+                block.iter().map(Score::score).sum()
+            })
+            .collect();
+        let total = blocks.iter().sum();
+
+        Ok((blocks, total))
+    }
+
+    /// Interpret rows that have already been parsed as rounds.
+    fn from_rows(rows: impl IntoIterator<Item = Row>) -> Self {
+        Self(rows.into_iter().map(Row::into).collect())
+    }
+}
+
+/// How rows of input are parsed, as configured on the command line.
+#[derive(Clone, Copy, Default)]
+struct ParseOptions {
+    separator: Separator,
+    /// The maximum number of rows to parse.
+    limit: Option<usize>,
+}
+
+impl ParseOptions {
+    fn from_flags(flags: &Flags) -> Result<Self, FlagError> {
+        Ok(Self {
+            separator: Separator::from_flags(flags)?,
+            limit: flags.parse("limit")?,
+        })
+    }
 }
 
+/// The character expected between the two values of a row.
+#[derive(Clone, Copy, Default)]
+enum Separator {
+    /// Any single ascii whitespace character.
+    #[default]
+    Whitespace,
+    Char(char),
+}
+
+impl Separator {
+    fn from_flags(flags: &Flags) -> Result<Self, FlagError> {
+        let separator = flags.parse("separator")?;
+        Ok(separator.map_or(Self::Whitespace, Self::Char))
+    }
+
+    fn matches(self, c: char) -> bool {
+        match self {
+            Self::Whitespace => c.is_ascii_whitespace(),
+            Self::Char(separator) => c == separator,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 struct Row {
-    left: Left,
-    right: Right,
+    opponent: Hand,
+    /// The second value as your hand, for a [`Match`].
+    you: Hand,
+    /// The second value as the outcome to play for, for a [`Strategy`].
+    outcome: Outcome,
 }
 
 impl FromStr for Row {
     type Err = ParseError;
 
     fn from_str(row: &str) -> Result<Self, Self::Err> {
+        Self::parse(row, Separator::Whitespace)
+    }
+}
+
+impl Row {
+    /// Parse each row of the input, up to the limit if there is one.
+    ///
+    /// An error names the line of its invalid row.
+    ///
+    /// Rows past the limit are never looked at,
+    /// so any errors in them go unreported.
+    fn parse_all(
+        input: &str,
+        options: ParseOptions,
+    ) -> impl Iterator<Item = Result<Self, ParseError>> + '_ {
+        let ParseOptions { separator, limit } = options;
+        input
+            .lines()
+            .take(limit.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(move |(index, row)| {
+                Self::parse(row, separator).map_err(|error| ParseError {
+                    line: index + 1,
+                    ..error
+                })
+            })
+    }
+
+    /// Parse a single row, which errors report as line 1.
+    ///
+    /// The row is only ever consumed char by char, never indexed,
+    /// so no input can cause a panic; an error tells which part of the row is invalid.
+    fn parse(row: &str, separator: Separator) -> Result<Self, ParseError> {
+        let invalid = |error| ParseError { line: 1, error };
         let mut chars = row.chars();
 
-        let left = match chars.next() {
-            Some('A') => Left::A,
-            Some('B') => Left::B,
-            Some('C') => Left::C,
-            _ => {
-                return Err(ParseError {
-                    invalid: row[0..].to_owned(),
-                });
-            }
+        // the opponent's hand is only ever 'A', 'B', or 'C'
+        let left = chars.next();
+        let opponent = left.filter(|c| matches!(c, 'A'..='C')).map(Hand::try_from);
+        let Some(Ok(opponent)) = opponent else {
+            return Err(invalid(RowError::LeftToken(left)));
         };
 
-        if chars.next() != Some(' ') {
-            return Err(ParseError {
-                invalid: row[1..].to_owned(),
-            });
+        if !chars.next().is_some_and(|c| separator.matches(c)) {
+            return Err(invalid(RowError::MissingSeparator));
         }
 
-        let right = match chars.next() {
-            Some('X') => Right::X,
-            Some('Y') => Right::Y,
-            Some('Z') => Right::Z,
-            _ => {
-                return Err(ParseError {
-                    invalid: row[2..].to_owned(),
-                });
-            }
+        // only 'X', 'Y', and 'Z' are both a hand and an outcome
+        let right = chars.next();
+        let Some((Ok(you), Ok(outcome))) = right.map(|c| (Hand::try_from(c), Outcome::try_from(c)))
+        else {
+            return Err(invalid(RowError::RightToken(right)));
         };
 
-        if chars.next().is_some() {
-            return Err(ParseError {
-                invalid: row[3..].to_owned(),
-            });
+        let rest = chars.as_str();
+        if !rest.is_empty() {
+            return Err(invalid(RowError::TrailingChars(rest.to_owned())));
         }
 
-        Ok(Self { left, right })
+        Ok(Self {
+            opponent,
+            you,
+            outcome,
+        })
     }
 }
 
-enum Left {
-    A,
-    B,
-    C,
-}
-
-enum Right {
-    X,
-    Y,
-    Z,
-}
-
 struct Match {
     you: Hand,
     opponent: Hand,
 }
 
+impl Match {
+    /// Returns the outcome and the hand you played, each along with its part of the score.
+    fn score_breakdown(&self) -> (Outcome, u64, Hand, u64) {
+        let outcome = self.you.match_with(self.opponent);
+        (outcome, outcome.score(), self.you, self.you.score())
+    }
+}
+
 impl Score for Match {
+    const LABEL: &'static str = "direct";
+
     fn score(&self) -> u64 {
-        self.you.match_with(self.opponent).score() + self.you.score()
+        let (_, outcome_score, _, hand_score) = self.score_breakdown();
+        outcome_score + hand_score
+    }
+
+    fn score_checked(&self) -> Option<u64> {
+        let (_, outcome_score, _, hand_score) = self.score_breakdown();
+        outcome_score.checked_add(hand_score)
     }
 }
 
 impl From<Row> for Match {
     fn from(row: Row) -> Self {
         Self {
-            you: row.right.into(),
-            opponent: row.left.into(),
+            you: row.you,
+            opponent: row.opponent,
         }
     }
 }
@@ -151,27 +717,52 @@ struct Strategy {
 }
 
 impl Score for Strategy {
+    const LABEL: &'static str = "strategy";
+
     fn score(&self) -> u64 {
         self.choice.score() + self.opponent.results_in(self.choice).score()
     }
+
+    fn score_checked(&self) -> Option<u64> {
+        let outcome = self.opponent.results_in(self.choice);
+        self.choice
+            .score_checked()?
+            .checked_add(outcome.score_checked()?)
+    }
 }
 
 impl From<Row> for Strategy {
     fn from(row: Row) -> Self {
         Self {
-            choice: row.right.into(),
-            opponent: row.left.into(),
+            choice: row.outcome,
+            opponent: row.opponent,
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Outcome {
     Loss,
     Draw,
     Win,
 }
 
+impl Outcome {
+    /// Every outcome, from worst to best.
+    const ALL: [Self; 3] = [Self::Loss, Self::Draw, Self::Win];
+
+    /// Returns the outcome of a match from the perspective of `you`.
+    fn from_hands(you: Hand, opponent: Hand) -> Self {
+        if you.beats(opponent) {
+            Self::Win
+        } else if opponent.beats(you) {
+            Self::Loss
+        } else {
+            Self::Draw
+        }
+    }
+}
+
 impl Score for Outcome {
     fn score(&self) -> u64 {
         match self {
@@ -182,49 +773,114 @@ impl Score for Outcome {
     }
 }
 
-impl From<Right> for Outcome {
-    fn from(right: Right) -> Self {
-        match right {
-            Right::X => Self::Loss,
-            Right::Y => Self::Draw,
-            Right::Z => Self::Win,
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let outcome = match self {
+            Self::Loss => "Loss",
+            Self::Draw => "Draw",
+            Self::Win => "Win",
+        };
+        f.write_str(outcome)
+    }
+}
+
+/// Parses `X`, `Y`, or `Z` as the outcome to play for.
+impl TryFrom<char> for Outcome {
+    type Error = InvalidChar;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'X' => Ok(Self::Loss),
+            'Y' => Ok(Self::Draw),
+            'Z' => Ok(Self::Win),
+            _ => Err(InvalidChar {
+                found: c,
+                expected: "'X', 'Y', or 'Z'",
+            }),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// A hand to play; hands form a cycle, so use [`Hand::beats`] rather than comparing them.
+///
+/// There's deliberately no [`Ord`], as any order of the hands would disagree with the game,
+/// where every hand beats one other hand and is beaten by the last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Hand {
     Rock,
     Paper,
     Scissors,
 }
 
+/// The hand beaten by each hand, indexed by the winning hand.
+///
+/// This is the single source of truth for the rules; every outcome is derived from it.
+const BEATS: [Hand; 3] = [Hand::Scissors, Hand::Rock, Hand::Paper];
+
+// against every hand, each outcome must be reached by playing a different hand,
+// so a mistake in the rules fails to compile
+const _: () = {
+    let mut i = 0;
+    while i < Hand::ALL.len() {
+        let mut played = [false; 3];
+        let mut j = 0;
+        while j < Outcome::ALL.len() {
+            let hand = Hand::ALL[i].results_in(Outcome::ALL[j]) as usize;
+            assert!(!played[hand], "two outcomes are reached by the same hand");
+            played[hand] = true;
+            j += 1;
+        }
+        i += 1;
+    }
+};
+
+// the table of the rules matches the game, where no hand beats itself
+const _: () = {
+    assert!(Hand::Rock.beats(Hand::Scissors));
+    assert!(Hand::Paper.beats(Hand::Rock));
+    assert!(Hand::Scissors.beats(Hand::Paper));
+
+    let mut i = 0;
+    while i < Hand::ALL.len() {
+        assert!(!Hand::ALL[i].beats(Hand::ALL[i]), "a hand beats itself");
+        i += 1;
+    }
+};
+
 impl Hand {
+    /// Every hand, in the order of their scores.
+    const ALL: [Self; 3] = [Self::Rock, Self::Paper, Self::Scissors];
+
     fn match_with(self, opponent: Self) -> Outcome {
-        use Hand::{Paper, Rock, Scissors};
-
-        if matches!(
-            (self, opponent),
-            (Rock, Scissors) | (Paper, Rock) | (Scissors, Paper)
-        ) {
-            Outcome::Win
-        } else if self == opponent {
-            Outcome::Draw
-        } else {
-            Outcome::Loss
-        }
+        Outcome::from_hands(self, opponent)
     }
 
-    fn results_in(self, outcome: Outcome) -> Self {
-        use Hand::{Paper, Rock, Scissors};
-        use Outcome::{Draw, Loss, Win};
+    /// Returns `true` if this hand wins against the other, as rock beats scissors.
+    const fn beats(self, other: Self) -> bool {
+        // `==` isn't available in const fns, but comparing discriminants is
+        self.wins_against() as usize == other as usize
+    }
 
-        match (self, outcome) {
-            (Rock, Loss) | (Paper, Win) | (Scissors, Draw) => Scissors,
-            (Rock, Win) | (Paper, Draw) | (Scissors, Loss) => Paper,
-            (Rock, Draw) | (Paper, Loss) | (Scissors, Win) => Rock,
+    /// Returns the hand to play against this one to get the given outcome.
+    const fn results_in(self, outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Loss => self.wins_against(),
+            Outcome::Draw => self,
+            Outcome::Win => self.loses_against(),
         }
     }
+
+    /// Returns the hand this one beats.
+    const fn wins_against(self) -> Self {
+        BEATS[self as usize]
+    }
+
+    /// Returns the hand that beats this one.
+    const fn loses_against(self) -> Self {
+        // the rules form a cycle of three, so the winner against this hand
+        // is the hand beaten by the one this hand beats
+        self.wins_against().wins_against()
+    }
 }
 
 impl Score for Hand {
@@ -237,40 +893,446 @@ impl Score for Hand {
     }
 }
 
-impl From<Left> for Hand {
-    fn from(left: Left) -> Self {
-        match left {
-            Left::A => Self::Rock,
-            Left::B => Self::Paper,
-            Left::C => Self::Scissors,
-        }
+impl Display for Hand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let hand = match self {
+            Self::Rock => "Rock",
+            Self::Paper => "Paper",
+            Self::Scissors => "Scissors",
+        };
+        f.write_str(hand)
     }
 }
 
-impl From<Right> for Hand {
-    fn from(right: Right) -> Self {
-        match right {
-            Right::X => Self::Rock,
-            Right::Y => Self::Paper,
-            Right::Z => Self::Scissors,
+/// Parses either the opponent's `A`, `B`, or `C`, or your `X`, `Y`, or `Z`.
+impl TryFrom<char> for Hand {
+    type Error = InvalidChar;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' | 'X' => Ok(Self::Rock),
+            'B' | 'Y' => Ok(Self::Paper),
+            'C' | 'Z' => Ok(Self::Scissors),
+            _ => Err(InvalidChar {
+                found: c,
+                expected: "'A', 'B', 'C', 'X', 'Y', or 'Z'",
+            }),
         }
     }
 }
 
 #[derive(Debug)]
 struct ParseError {
-    invalid: String,
+    /// The 1-based line of input containing the invalid row.
+    line: usize,
+    error: RowError,
+}
+
+/// The part of a row that's invalid.
+#[derive(Debug)]
+enum RowError {
+    /// The first value isn't 'A', 'B', or 'C', or is [`None`] if the row is empty.
+    LeftToken(Option<char>),
+    /// The first value isn't followed by the separator.
+    MissingSeparator,
+    /// The second value isn't 'X', 'Y', or 'Z', or is [`None`] if the row ends before it.
+    RightToken(Option<char>),
+    /// The row goes on after the second value.
+    TrailingChars(String),
+}
+
+/// An error returned when a character doesn't stand for a hand or an outcome.
+#[derive(Debug)]
+struct InvalidChar {
+    found: char,
+    /// The characters that would have been valid, as listed in messages.
+    expected: &'static str,
+}
+
+impl Error for InvalidChar {}
+
+impl Display for InvalidChar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { found, expected } = self;
+        write!(f, "expected {expected}, found '{found}'")
+    }
 }
 
 trait Score {
+    /// Names what is scored, so output can tell apart each interpretation of a row.
+    const LABEL: &'static str = "score";
+
     fn score(&self) -> u64;
+
+    /// Returns the score, or [`None`] if it can't be represented, so sums of scores can fail
+    /// instead of wrapping.
+    ///
+    /// By default this is just [`Self::score`], for scores that can't overflow;
+    /// a score added up from other scores should add them with checked arithmetic.
+    fn score_checked(&self) -> Option<u64> {
+        Some(self.score())
+    }
+
+    fn label(&self) -> &'static str {
+        Self::LABEL
+    }
 }
 
-impl Error for ParseError {}
+/// A number that scores can be summed into.
+trait Total: Sized {
+    const ZERO: Self;
+
+    /// Add a score, returning [`None`] if the result can't be represented.
+    fn checked_add_score(self, score: u64) -> Option<Self>;
+}
+
+impl Total for u64 {
+    const ZERO: Self = 0;
+
+    fn checked_add_score(self, score: u64) -> Option<Self> {
+        self.checked_add(score)
+    }
+}
+
+impl Total for u128 {
+    const ZERO: Self = 0;
+
+    fn checked_add_score(self, score: u64) -> Option<Self> {
+        self.checked_add(score.into())
+    }
+}
+
+impl Total for f64 {
+    const ZERO: Self = 0.0;
+
+    fn checked_add_score(self, score: u64) -> Option<Self> {
+        Some(self + score as f64).filter(|total| total.is_finite())
+    }
+}
+
+#[derive(Debug)]
+struct Overflow {
+    /// The label of the scores being summed.
+    label: &'static str,
+    round: usize,
+}
+
+impl Error for Overflow {}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { label, round } = self;
+        write!(
+            f,
+            "total {label} score overflowed at round {round}; try '--wide'"
+        )
+    }
+}
+
+/// An error scoring one player of a tournament.
+#[derive(Debug)]
+struct PlayerError {
+    player: String,
+    error: Box<dyn Error + Send + Sync>,
+}
+
+impl PlayerError {
+    fn new(file: &InputFile, error: impl Error + Send + Sync + 'static) -> Self {
+        Self {
+            player: file.name().to_owned(),
+            error: Box::new(error),
+        }
+    }
+}
+
+impl Error for PlayerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+impl Display for PlayerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let player = &self.player;
+        write!(f, "can't score player '{player}'")
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let invalid = self.invalid.as_str();
-        write!(f, "found invalid input '{invalid}'")
+        let line = self.line;
+        write!(f, "line {line} isn't a valid row")
+    }
+}
+
+impl Error for RowError {}
+
+impl Display for RowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeftToken(Some(c)) => write!(f, "expected 'A', 'B', or 'C', found '{c}'"),
+            Self::LeftToken(None) => write!(f, "expected 'A', 'B', or 'C', found an empty row"),
+            Self::MissingSeparator => write!(f, "expected a separator after the first value"),
+            Self::RightToken(Some(c)) => write!(f, "expected 'X', 'Y', or 'Z', found '{c}'"),
+            Self::RightToken(None) => {
+                write!(f, "expected 'X', 'Y', or 'Z', found the end of the row")
+            }
+            Self::TrailingChars(rest) => write!(f, "expected the end of the row, found '{rest}'"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn parse_errors_are_invalid_input() {
+        let options = ParseOptions::default();
+        let error = solve("A Y\nA Q\n", options, &Flags::default()).unwrap_err();
+        assert_eq!(error.exit_code(), 3);
+
+        let error = self_check("A Q\n", options).unwrap_err();
+        assert_eq!(error.exit_code(), 3);
+    }
+
+    fn match_score(row: &str, separator: Separator) -> Option<u64> {
+        let row = Row::parse(row, separator).ok()?;
+        Some(Match::from(row).score())
+    }
+
+    #[test]
+    fn rows_are_separated_by_any_whitespace_by_default() {
+        assert_eq!(match_score("A X", Separator::Whitespace), Some(4));
+        assert_eq!(match_score("A\tX", Separator::Whitespace), Some(4));
+        assert_eq!(match_score("A|X", Separator::Whitespace), None);
+    }
+
+    #[test]
+    fn rows_are_separated_by_a_configured_separator() {
+        assert_eq!(match_score("A|X", Separator::Char('|')), Some(4));
+        assert_eq!(match_score("A X", Separator::Char('|')), None);
+        assert_eq!(match_score("AX", Separator::Char('|')), None);
+    }
+
+    /// Returns the input read from a file of `contents`, with the builtin `flags` in `args`.
+    fn read_with_flags(name: &str, contents: &str, args: &[&str]) -> String {
+        let file = env::temp_dir().join(format!("rock-paper-scissors-{name}.txt"));
+        fs::write(&file, contents).unwrap();
+
+        let description = Description {
+            name: "rock-paper-scissors",
+            bin_name: "rock-paper-scissors".into(),
+            description: "",
+            version: (0, 0, 0),
+            flags: &[],
+            standalone_flags: &[],
+            env_vars: &[],
+            examples: &[],
+        };
+        let file = file.to_string_lossy().into_owned();
+        let args = iter::once("rock-paper-scissors").chain(args.iter().copied());
+        let args = args.chain([file.as_str()]).map(str::to_owned);
+        input::get_with_args(description, args).unwrap()
+    }
+
+    #[test]
+    fn rows_after_a_skipped_header_parse() {
+        let input = read_with_flags(
+            "skip-header",
+            "# generated\nA Y\nB X\nC Z\n",
+            &["--skip-header"],
+        );
+        assert_eq!(matches(&input).score().unwrap(), 15);
+
+        let contents = "# generated\r\n# seed 357\r\nA Y\r\nB X\r\nC Z\r\n";
+        let args = ["--header-lines", "2", "--normalize-newlines"];
+        let input = read_with_flags("header-lines", contents, &args);
+        assert_eq!(matches(&input).score().unwrap(), 15);
+    }
+
+    #[test]
+    fn parse_errors_name_the_line_of_the_invalid_row() {
+        let rows = Row::parse_all("A Y\nB X\nC Q\nA Z\n", ParseOptions::default());
+        let lines: Vec<_> = rows.map(|row| row.err().map(|error| error.line)).collect();
+        assert_eq!(lines, [None, None, Some(3), None]);
+    }
+
+    #[test]
+    fn blocks_are_separated_by_empty_lines() {
+        let blocks = |input| Matches::<Match>::score_by_block(input, ParseOptions::default());
+
+        // the rows score 8, 1, and 6
+        let (scores, total) = blocks("\nA Y\n\n\nB X\nC Z\n\n\n").unwrap();
+        assert_eq!(scores, [0, 8, 0, 7], "leading and consecutive empty lines");
+        assert_eq!(total, 15);
+
+        assert_eq!(blocks("\n\n").unwrap(), (vec![], 0), "only empty lines");
+
+        let error = blocks("A Y\n\nB X\nC Q\n").unwrap_err();
+        assert_eq!(error.line, 4);
+    }
+
+    #[test]
+    fn limit_counts_rows_of_blocks() {
+        let options = ParseOptions {
+            limit: Some(2),
+            ..ParseOptions::default()
+        };
+        let blocks = Matches::<Match>::score_by_block("A Y\n\n\nB X\n\nC Q\n", options);
+        assert_eq!(blocks.unwrap(), (vec![8, 0, 1], 9));
+    }
+
+    #[test]
+    fn parsing_random_rows_never_panics() {
+        const CHARS: [char; 12] = ['A', 'B', 'C', 'X', 'Y', 'Z', ' ', '\t', 'a', 'é', '🪨', '✂'];
+        let mut rng = SplitMix64::new(352);
+
+        for _ in 0..10_000 {
+            let len = (rng.next_u64() % 6) as usize;
+            let row: String = (0..len)
+                .map(|_| CHARS[(rng.next_u64() % CHARS.len() as u64) as usize])
+                .collect();
+
+            let chars: Vec<char> = row.chars().collect();
+            let valid = matches!(
+                chars[..],
+                ['A' | 'B' | 'C', separator, 'X' | 'Y' | 'Z'] if separator.is_ascii_whitespace()
+            );
+            let parsed = Row::parse(&row, Separator::Whitespace);
+            assert_eq!(parsed.is_ok(), valid, "{row:?}");
+        }
+    }
+
+    fn streaks(input: &str) -> (usize, usize) {
+        let matches = matches(input);
+        (matches.longest_loss_streak(), matches.longest_win_streak())
+    }
+
+    #[test]
+    fn longest_streaks_count_consecutive_outcomes() {
+        // 'A Y' is a win, 'A Z' a loss, and 'A X' a draw
+        assert_eq!(streaks("A Y\nA Z\nA Y\nA Z\n"), (1, 1));
+        assert_eq!(streaks("A Z\nA Z\nA Y\nA Y\nA Y\nA X\nA Y\nA Z\n"), (2, 3));
+        assert_eq!(streaks("A X\nA X\n"), (0, 0));
+        assert_eq!(streaks(""), (0, 0));
+    }
+
+    fn matches(input: &str) -> Matches<Match> {
+        Matches::parse(input, ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn weighted_score_multiplies_each_round_by_its_weight() {
+        // the rounds score 8, 1, and 6
+        let matches = matches("A Y\nB X\nC Z\n");
+        assert_eq!(
+            matches.weighted_score(|round| round as u64 + 1).unwrap(),
+            28
+        );
+        assert_eq!(
+            matches.weighted_score(|_| 1).unwrap(),
+            matches.score().unwrap()
+        );
+    }
+
+    #[test]
+    fn weighted_score_fails_on_overflow() {
+        let matches = matches("A Y\nB X\nC Z\n");
+
+        let error = matches.weighted_score(|_| u64::MAX).unwrap_err();
+        assert_eq!(error.round, 1, "weighted score overflows");
+
+        let error = matches.weighted_score(|_| u64::MAX / 8).unwrap_err();
+        assert_eq!(error.round, 2, "sum overflows");
+    }
+
+    #[test]
+    fn par_score_is_the_same_as_score() {
+        let rounds = matches(&generate(1000, Some(381)));
+        let score = rounds.score().unwrap();
+        assert_eq!(rounds.par_score(4).unwrap(), score);
+        assert_eq!(rounds.par_score(1).unwrap(), score);
+        assert_eq!(rounds.par_score(0).unwrap(), score);
+
+        // more threads than rounds
+        assert_eq!(matches("A Y\nB X\nC Z\n").par_score(8).unwrap(), 15);
+
+        assert_eq!(matches("").par_score(4).unwrap(), 0);
+    }
+
+    #[test]
+    fn every_total_is_the_same_as_score() {
+        let rounds = matches(&generate(100, Some(314)));
+        let score = rounds.score().unwrap();
+        assert_eq!(rounds.total::<u128>().unwrap(), u128::from(score));
+        assert_eq!(rounds.total::<u64>().unwrap(), score);
+        assert_eq!(rounds.total::<f64>().unwrap(), score as f64);
+    }
+
+    #[test]
+    fn playing_the_hand_for_an_outcome_results_in_it() {
+        for opponent in Hand::ALL {
+            for you in Hand::ALL {
+                let outcome = you.match_with(opponent);
+                assert!(opponent.results_in(outcome) == you);
+            }
+            for outcome in Outcome::ALL {
+                assert!(opponent.results_in(outcome).match_with(opponent) == outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn hands_parse_from_either_column() {
+        let hands: Vec<_> = "ABCXYZ"
+            .chars()
+            .map(|c| Hand::try_from(c).unwrap())
+            .collect();
+        assert_eq!(hands, Hand::ALL.repeat(2));
+
+        let error = Hand::try_from('D').unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected 'A', 'B', 'C', 'X', 'Y', or 'Z', found 'D'"
+        );
+    }
+
+    #[test]
+    fn outcomes_parse_from_the_second_column() {
+        let outcomes: Vec<_> = "XYZ"
+            .chars()
+            .map(|c| Outcome::try_from(c).unwrap())
+            .collect();
+        assert_eq!(outcomes, Outcome::ALL);
+
+        let error = Outcome::try_from('A').unwrap_err();
+        assert_eq!(error.to_string(), "expected 'X', 'Y', or 'Z', found 'A'");
+    }
+
+    #[test]
+    fn parse_errors_name_the_invalid_part_of_the_row() {
+        let message = |row: &str| {
+            let error = Row::parse(row, Separator::Whitespace).err().unwrap();
+            assert_eq!(error.to_string(), "line 1 isn't a valid row");
+            error.source().unwrap().to_string()
+        };
+
+        assert_eq!(message(""), "expected 'A', 'B', or 'C', found an empty row");
+        assert_eq!(message("Q X"), "expected 'A', 'B', or 'C', found 'Q'");
+        assert_eq!(message("AX"), "expected a separator after the first value");
+        assert_eq!(
+            message("A "),
+            "expected 'X', 'Y', or 'Z', found the end of the row"
+        );
+        assert_eq!(message("A Q"), "expected 'X', 'Y', or 'Z', found 'Q'");
+        assert_eq!(message("A X Y"), "expected the end of the row, found ' Y'");
     }
 }